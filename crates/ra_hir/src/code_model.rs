@@ -6,12 +6,14 @@ use either::Either;
 use hir_def::{
     adt::StructKind,
     adt::VariantData,
-    builtin_type::BuiltinType,
+    builtin_type::{BuiltinInt, BuiltinType},
     docs::Documentation,
     expr::{BindingAnnotation, Pat, PatId},
+    generics::{TypeParamProvenance, WherePredicateTarget},
+    path::path,
     per_ns::PerNs,
     resolver::{HasResolver, Resolver},
-    type_ref::{Mutability, TypeRef},
+    type_ref::{Mutability, TypeBound, TypeRef},
     AdtId, AssocContainerId, ConstId, DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule,
     ImplId, LocalEnumVariantId, LocalFieldId, LocalModuleId, Lookup, ModuleId, StaticId, StructId,
     TraitId, TypeAliasId, TypeParamId, UnionId,
@@ -25,8 +27,9 @@ use hir_ty::{
     autoderef,
     display::{HirDisplayError, HirFormatter},
     expr::ExprValidator,
-    method_resolution, ApplicationTy, Canonical, InEnvironment, Substs, TraitEnvironment, Ty,
-    TyDefId, TypeCtor,
+    method_resolution,
+    primitive::Uncertain,
+    ApplicationTy, Canonical, InEnvironment, Substs, TraitEnvironment, Ty, TyDefId, TypeCtor,
 };
 use ra_db::{CrateId, CrateName, Edition, FileId};
 use ra_prof::profile;
@@ -637,6 +640,32 @@ impl Function {
         db.function_data(self.id).params.clone()
     }
 
+    pub fn ret_type(self, db: &dyn HirDatabase) -> TypeRef {
+        db.function_data(self.id).ret_type.clone()
+    }
+
+    pub fn is_const(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_const
+    }
+
+    /// Checks whether this returns the `Result` or `Option` of `core` or
+    /// `std`, looking through type aliases.
+    pub fn is_fallible(self, db: &dyn HirDatabase) -> bool {
+        let ret_ty = db.callable_item_signature(self.id.into()).value.ret().clone();
+        let enum_ = match ret_ty {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::EnumId(it)), .. }) => it,
+            _ => return false,
+        };
+        let resolver = self.id.resolver(db.upcast());
+        let paths = [
+            path![core::result::Result],
+            path![core::option::Option],
+            path![std::result::Result],
+            path![std::option::Option],
+        ];
+        paths.iter().any(|path| resolver.resolve_known_enum(db.upcast(), path) == Some(enum_))
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
         let _p = profile("Function::diagnostics");
         let infer = db.infer(self.id.into());
@@ -725,6 +754,69 @@ impl Trait {
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).auto
     }
+
+    pub fn is_unsafe(self, db: &dyn HirDatabase) -> bool {
+        db.trait_data(self.id).is_unsafe
+    }
+
+    /// Checks whether the trait can be used as `dyn Trait`. This looks at the
+    /// signatures of the items and at a `Sized` supertrait, but not at other
+    /// supertraits.
+    pub fn is_object_safe(self, db: &dyn HirDatabase) -> bool {
+        let generics = db.generic_params(self.id.into());
+        let requires_sized = generics.where_predicates.iter().any(|pred| {
+            let is_self = match pred.target {
+                WherePredicateTarget::TypeParam(id) => {
+                    generics.types[id].provenance == TypeParamProvenance::TraitSelf
+                }
+                WherePredicateTarget::TypeRef(ref type_ref) => is_self_type(type_ref),
+            };
+            is_self && is_sized_bound(&pred.bound)
+        });
+        if requires_sized {
+            return false;
+        }
+
+        db.trait_data(self.id).items.iter().all(|&(_, item)| match item {
+            AssocItemId::ConstId(_) => false,
+            AssocItemId::TypeAliasId(_) => true,
+            AssocItemId::FunctionId(func) => {
+                let generics = db.generic_params(func.into());
+                // Methods with `where Self: Sized` are excluded from the trait object.
+                let excluded = generics.where_predicates.iter().any(|pred| match pred.target {
+                    WherePredicateTarget::TypeRef(ref type_ref) => {
+                        is_self_type(type_ref) && is_sized_bound(&pred.bound)
+                    }
+                    WherePredicateTarget::TypeParam(_) => false,
+                });
+                if excluded {
+                    return true;
+                }
+
+                let data = db.function_data(func);
+                let mut mentions_self = false;
+                let non_receiver = data.params.iter().skip(data.has_self_param as usize);
+                for type_ref in non_receiver.chain(std::iter::once(&data.ret_type)) {
+                    type_ref.walk(&mut |it| mentions_self |= is_self_type(it));
+                }
+                data.has_self_param && generics.types.is_empty() && !mentions_self
+            }
+        })
+    }
+}
+
+fn is_self_type(type_ref: &TypeRef) -> bool {
+    match type_ref {
+        TypeRef::Path(path) => path.mod_path().as_ident() == Some(&name![Self]),
+        _ => false,
+    }
+}
+
+fn is_sized_bound(bound: &TypeBound) -> bool {
+    match bound {
+        TypeBound::Path(path) => path.mod_path().segments.last() == Some(&name![Sized]),
+        TypeBound::Error => false,
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1046,6 +1138,16 @@ impl ImplDef {
         db.impl_data(self.id).is_negative
     }
 
+    /// Checks whether this implements the `drop` lang item trait.
+    pub fn is_drop_impl(&self, db: &dyn HirDatabase) -> bool {
+        let krate = self.module(db).id.krate;
+        let drop_trait = db.lang_item(krate, "drop".into()).and_then(|it| it.as_trait());
+        match (drop_trait, db.impl_trait(self.id)) {
+            (Some(drop_trait), Some(trait_ref)) => trait_ref.value.trait_ == drop_trait,
+            _ => false,
+        }
+    }
+
     pub fn module(&self, db: &dyn HirDatabase) -> Module {
         self.id.lookup(db.upcast()).container.module(db.upcast()).into()
     }
@@ -1121,6 +1223,34 @@ impl Type {
         matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Bool, .. }))
     }
 
+    /// Returns the integer type this is, if it is one and inference settled it.
+    pub fn as_builtin_int(&self) -> Option<BuiltinInt> {
+        match self.ty.value {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Int(Uncertain::Known(int)), .. }) => {
+                Some(BuiltinInt { signedness: int.signedness, bitness: int.bitness })
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether this is a primitive type, like `u32` or `str`, a slice
+    /// or an array.
+    pub fn is_builtin(&self) -> bool {
+        match &self.ty.value {
+            Ty::Apply(a_ty) => matches!(
+                a_ty.ctor,
+                TypeCtor::Bool
+                    | TypeCtor::Char
+                    | TypeCtor::Int(_)
+                    | TypeCtor::Float(_)
+                    | TypeCtor::Str
+                    | TypeCtor::Slice
+                    | TypeCtor::Array
+            ),
+            _ => false,
+        }
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(
             self.ty.value,
@@ -1154,6 +1284,35 @@ impl Type {
         )
     }
 
+    /// Checks that particular type `ty` implements `std::marker::Copy`.
+    pub fn is_copy(&self, db: &dyn HirDatabase) -> bool {
+        self.impls_lang_trait(db, "copy")
+    }
+
+    /// Checks that particular type `ty` implements `std::ops::Drop`.
+    pub fn impls_drop(&self, db: &dyn HirDatabase) -> bool {
+        self.impls_lang_trait(db, "drop")
+    }
+
+    fn impls_lang_trait(&self, db: &dyn HirDatabase, name: &str) -> bool {
+        let krate = self.krate;
+
+        let trait_ = db.lang_item(krate, name.into()).and_then(|it| it.as_trait());
+        let trait_ = match trait_ {
+            Some(it) => it,
+            None => return false,
+        };
+
+        let canonical_ty = Canonical { value: self.ty.value.clone(), num_vars: 0 };
+        method_resolution::implements_trait(
+            &canonical_ty,
+            db,
+            self.ty.environment.clone(),
+            krate,
+            trait_,
+        )
+    }
+
     pub fn impls_trait(&self, db: &dyn HirDatabase, trait_: Trait, args: &[Type]) -> bool {
         let trait_ref = hir_ty::TraitRef {
             trait_: trait_.id,
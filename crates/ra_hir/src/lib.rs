@@ -64,11 +64,11 @@ pub use hir_def::{
     adt::StructKind,
     attr::Attrs,
     body::scope::ExprScopes,
-    builtin_type::BuiltinType,
+    builtin_type::{BuiltinInt, BuiltinType, IntBitness, Signedness},
     docs::Documentation,
     nameres::ModuleSource,
     path::{ModPath, Path, PathKind},
-    type_ref::Mutability,
+    type_ref::{Mutability, TypeRef},
 };
 pub use hir_expand::{
     hygiene::Hygiene, name::Name, HirFileId, InFile, MacroCallId, MacroCallLoc, MacroDefId,
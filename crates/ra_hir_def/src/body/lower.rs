@@ -488,6 +488,7 @@ impl ExprCollector<'_> {
 
             // FIXME implement HIR for these:
             ast::Expr::Label(_e) => self.alloc_expr(Expr::Missing, syntax_ptr),
+            ast::Expr::LetExpr(_e) => self.alloc_expr(Expr::Missing, syntax_ptr),
         }
     }
 
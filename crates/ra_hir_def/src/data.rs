@@ -34,6 +34,8 @@ pub struct FunctionData {
     /// True if the first param is `self`. This is relevant to decide whether this
     /// can be called as a method.
     pub has_self_param: bool,
+    /// True for a `const fn`, which can be evaluated at compile time.
+    pub is_const: bool,
     pub visibility: RawVisibility,
 }
 
@@ -89,7 +91,9 @@ impl FunctionData {
         let visibility =
             RawVisibility::from_ast_with_default(db, vis_default, src.map(|s| s.visibility()));
 
-        let sig = FunctionData { name, params, ret_type, has_self_param, visibility, attrs };
+        let is_const = src.value.const_token().is_some();
+        let sig =
+            FunctionData { name, params, ret_type, has_self_param, is_const, visibility, attrs };
         Arc::new(sig)
     }
 }
@@ -146,6 +150,7 @@ pub struct TraitData {
     pub name: Name,
     pub items: Vec<(Name, AssocItemId)>,
     pub auto: bool,
+    pub is_unsafe: bool,
 }
 
 impl TraitData {
@@ -154,6 +159,7 @@ impl TraitData {
         let src = tr_loc.source(db);
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
         let auto = src.value.auto_token().is_some();
+        let is_unsafe = src.value.unsafe_token().is_some();
         let module_id = tr_loc.container.module(db);
 
         let container = AssocContainerId::TraitId(tr);
@@ -175,7 +181,7 @@ impl TraitData {
                 container,
             ));
         }
-        Arc::new(TraitData { name, items, auto })
+        Arc::new(TraitData { name, items, auto, is_unsafe })
     }
 
     pub fn associated_types(&self) -> impl Iterator<Item = TypeAliasId> + '_ {
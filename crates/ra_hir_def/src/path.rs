@@ -312,6 +312,9 @@ pub use hir_expand::name as __name;
 macro_rules! __known_path {
     (std::iter::IntoIterator) => {};
     (std::result::Result) => {};
+    (std::option::Option) => {};
+    (core::result::Result) => {};
+    (core::option::Option) => {};
     (std::ops::Range) => {};
     (std::ops::RangeFrom) => {};
     (std::ops::RangeFull) => {};
@@ -156,6 +156,7 @@ pub mod known {
         ops,
         future,
         result,
+        option,
         boxed,
         // Components of known path (type name)
         IntoIterator,
@@ -164,6 +165,7 @@ pub mod known {
         Ok,
         Future,
         Result,
+        Option,
         Output,
         Target,
         Box,
@@ -176,6 +178,7 @@ pub mod known {
         Neg,
         Not,
         Index,
+        Sized,
         // Builtin macros
         file,
         column,
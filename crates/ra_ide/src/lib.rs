@@ -72,7 +72,8 @@ pub use crate::{
     runnables::{Runnable, RunnableKind, TestId},
     ssr::SsrError,
     syntax_highlighting::{
-        Highlight, HighlightModifier, HighlightModifiers, HighlightTag, HighlightedRange,
+        Highlight, HighlightConfig, HighlightModifier, HighlightModifiers, HighlightTag,
+        HighlightedRange,
     },
 };
 
@@ -447,13 +448,23 @@ impl Analysis {
     }
 
     /// Computes syntax highlighting for the given file
-    pub fn highlight(&self, file_id: FileId) -> Cancelable<Vec<HighlightedRange>> {
-        self.with_db(|db| syntax_highlighting::highlight(db, file_id, None))
+    pub fn highlight(
+        &self,
+        file_id: FileId,
+        config: &HighlightConfig,
+    ) -> Cancelable<Vec<HighlightedRange>> {
+        self.with_db(|db| syntax_highlighting::highlight(db, file_id, None, config))
     }
 
     /// Computes syntax highlighting for the given file range.
-    pub fn highlight_range(&self, frange: FileRange) -> Cancelable<Vec<HighlightedRange>> {
-        self.with_db(|db| syntax_highlighting::highlight(db, frange.file_id, Some(frange.range)))
+    pub fn highlight_range(
+        &self,
+        frange: FileRange,
+        config: &HighlightConfig,
+    ) -> Cancelable<Vec<HighlightedRange>> {
+        self.with_db(|db| {
+            syntax_highlighting::highlight(db, frange.file_id, Some(frange.range), config)
+        })
     }
 
     /// Computes syntax highlighting for the given file.
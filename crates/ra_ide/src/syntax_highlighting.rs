@@ -2,20 +2,21 @@
 
 mod tags;
 mod html;
+mod markdown;
 #[cfg(test)]
 mod tests;
 
-use hir::{Name, Semantics};
+use hir::{AsAssocItem, HasAttrs, Name, Semantics};
 use ra_ide_db::{
     defs::{classify_name, classify_name_ref, Definition, NameClass, NameRefClass},
     RootDatabase,
 };
 use ra_prof::profile;
 use ra_syntax::{
-    ast::{self, HasFormatSpecifier, HasQuotes, HasStringValue},
+    ast::{self, ArgListOwner, HasFormatSpecifier, HasQuotes, HasStringValue},
     AstNode, AstToken, Direction, NodeOrToken, SyntaxElement,
     SyntaxKind::*,
-    SyntaxToken, TextRange, WalkEvent, T,
+    SyntaxToken, TextRange, TextSize, WalkEvent, T,
 };
 use rustc_hash::FxHashMap;
 
@@ -25,6 +26,31 @@ use ast::FormatSpecifier;
 pub(crate) use html::highlight_as_html;
 pub use tags::{Highlight, HighlightModifier, HighlightModifiers, HighlightTag};
 
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct HighlightConfig {
+    /// Flags `.clone()` calls on `Copy` types, which requires type inference.
+    pub redundant_clone: bool,
+    /// Flags traits used as `dyn Trait` which are not object safe.
+    pub object_safety: bool,
+    /// Flags functions returning a `Result` or an `Option`.
+    pub fallible_functions: bool,
+    /// Flags constants and statics whose names are not in `SCREAMING_CASE`.
+    pub naming_case: bool,
+    /// Names of the types to flag as smart pointers, like `Box` or `Rc`.
+    pub smart_pointers: Vec<String>,
+    /// Highlights markdown headings, code spans and emphasis in doc comments.
+    pub doc_markdown: bool,
+    /// Names of the types to flag as markers, like `PhantomData`. When set,
+    /// structs without fields are flagged too.
+    pub marker_types: Vec<String>,
+    /// Flags references to types implementing `Drop`, which requires trait
+    /// solving.
+    pub drop_types: bool,
+    /// Flags methods of builtin types like `str::len`, which requires
+    /// resolving the impl they are defined in.
+    pub builtin_methods: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct HighlightedRange {
     pub range: TextRange,
@@ -111,6 +137,7 @@ pub(crate) fn highlight(
     db: &RootDatabase,
     file_id: FileId,
     range_to_highlight: Option<TextRange>,
+    config: &HighlightConfig,
 ) -> Vec<HighlightedRange> {
     let _p = profile("highlight");
     let sema = Semantics::new(db);
@@ -166,10 +193,21 @@ pub(crate) fn highlight(
                         highlight: HighlightTag::Macro.into(),
                         binding_hash: None,
                     });
+                    // The `!` gets its own range, nested in the one of the name.
+                    if let Some(excl) = mc.excl_token() {
+                        stack.push();
+                        stack.add(HighlightedRange {
+                            range: excl.text_range(),
+                            highlight: HighlightTag::Punctuation.into(),
+                            binding_hash: None,
+                        });
+                        stack.pop();
+                    }
                 }
                 if let Some(name) = mc.is_macro_rules() {
                     if let Some((highlight, binding_hash)) = highlight_element(
                         &sema,
+                        config,
                         &mut bindings_shadow_count,
                         name.syntax().clone().into(),
                     ) {
@@ -241,16 +279,19 @@ pub(crate) fn highlight(
 
         if let Some(token) = element.as_token().cloned().and_then(ast::RawString::cast) {
             let expanded = element_to_highlight.as_token().unwrap().clone();
-            if highlight_injection(&mut stack, &sema, token, expanded).is_some() {
+            if highlight_injection(&mut stack, &sema, config, token, expanded).is_some() {
                 continue;
             }
         }
 
         let is_format_string = format_string.as_ref() == Some(&element_to_highlight);
 
-        if let Some((highlight, binding_hash)) =
-            highlight_element(&sema, &mut bindings_shadow_count, element_to_highlight.clone())
-        {
+        if let Some((highlight, binding_hash)) = highlight_element(
+            &sema,
+            config,
+            &mut bindings_shadow_count,
+            element_to_highlight.clone(),
+        ) {
             stack.add(HighlightedRange { range, highlight, binding_hash });
             if let Some(string) =
                 element_to_highlight.as_token().cloned().and_then(ast::String::cast)
@@ -272,6 +313,14 @@ pub(crate) fn highlight(
                 element_to_highlight.as_token().cloned().and_then(ast::RawString::cast)
             {
                 stack.push();
+                let delimiters = raw_string_delimiters(&string);
+                if let Some((prefix, _)) = delimiters {
+                    stack.add(HighlightedRange {
+                        range: prefix + range.start(),
+                        highlight: HighlightTag::Punctuation.into(),
+                        binding_hash: None,
+                    });
+                }
                 if is_format_string {
                     string.lex_format_specifier(|piece_range, kind| {
                         if let Some(highlight) = highlight_format_specifier(kind) {
@@ -283,6 +332,28 @@ pub(crate) fn highlight(
                         }
                     });
                 }
+                if let Some((_, suffix)) = delimiters.filter(|(_, suffix)| !suffix.is_empty()) {
+                    stack.add(HighlightedRange {
+                        range: suffix + range.start(),
+                        highlight: HighlightTag::Punctuation.into(),
+                        binding_hash: None,
+                    });
+                }
+                stack.pop();
+            } else if let Some(comment) = element_to_highlight
+                .as_token()
+                .cloned()
+                .and_then(ast::Comment::cast)
+                .filter(|comment| config.doc_markdown && comment.kind().doc.is_some())
+            {
+                stack.push();
+                for (piece_range, modifier) in markdown::doc_markdown_ranges(comment.text()) {
+                    stack.add(HighlightedRange {
+                        range: piece_range + range.start(),
+                        highlight: HighlightTag::Comment | modifier,
+                        binding_hash: None,
+                    });
+                }
                 stack.pop();
             }
         }
@@ -291,6 +362,19 @@ pub(crate) fn highlight(
     stack.flattened()
 }
 
+/// Returns the ranges of the `r#` prefix and the `#` suffix of a raw string,
+/// relative to the start of the literal.
+fn raw_string_delimiters(string: &ast::RawString) -> Option<(TextRange, TextRange)> {
+    let offsets = string.quote_offsets()?;
+    let start = string.syntax().text_range().start();
+    let quote = TextSize::of('"');
+    let (open, close) = (offsets.quotes[0] - start, offsets.quotes[1] - start);
+    Some((
+        TextRange::new(open.start(), open.end() - quote),
+        TextRange::new(close.start() + quote, close.end()),
+    ))
+}
+
 fn highlight_format_specifier(kind: FormatSpecifier) -> Option<HighlightTag> {
     Some(match kind {
         FormatSpecifier::Open
@@ -327,6 +411,7 @@ fn macro_call_range(macro_call: &ast::MacroCall) -> Option<TextRange> {
 
 fn highlight_element(
     sema: &Semantics<RootDatabase>,
+    config: &HighlightConfig,
     bindings_shadow_count: &mut FxHashMap<Name, u32>,
     element: SyntaxElement,
 ) -> Option<(Highlight, Option<u64>)> {
@@ -342,6 +427,20 @@ fn highlight_element(
         NAME => {
             let name = element.into_node().and_then(ast::Name::cast).unwrap();
             let name_kind = classify_name(sema, &name);
+            let is_provided_method = is_provided_trait_method(&name);
+            let is_bad_case = match &name_kind {
+                Some(NameClass::Definition(Definition::ModuleDef(hir::ModuleDef::Const(_))))
+                | Some(NameClass::Definition(Definition::ModuleDef(hir::ModuleDef::Static(_)))) => {
+                    config.naming_case && !is_screaming_case(&name)
+                }
+                _ => false,
+            };
+            let is_fallible = match &name_kind {
+                Some(NameClass::Definition(Definition::ModuleDef(hir::ModuleDef::Function(
+                    func,
+                )))) => config.fallible_functions && func.is_fallible(db),
+                _ => false,
+            };
 
             if let Some(NameClass::Definition(Definition::Local(local))) = &name_kind {
                 if let Some(name) = local.name(db) {
@@ -350,23 +449,57 @@ fn highlight_element(
                     binding_hash = Some(calc_binding_hash(&name, *shadow_count))
                 }
             };
+            // Type params are hashed by identity, so the `T`s of different items differ.
+            if let Some(NameClass::Definition(Definition::TypeParam(type_param))) = &name_kind {
+                binding_hash = Some(hash(*type_param));
+            }
 
-            match name_kind {
+            let mut h = match name_kind {
                 Some(NameClass::Definition(def)) => {
                     highlight_name(db, def) | HighlightModifier::Definition
                 }
                 Some(NameClass::ConstReference(def)) => highlight_name(db, def),
                 None => highlight_name_by_syntax(name) | HighlightModifier::Definition,
+            };
+            if is_provided_method {
+                h |= HighlightModifier::Provided;
+            }
+            if is_fallible {
+                h |= HighlightModifier::Fallible;
             }
+            if is_bad_case {
+                h |= HighlightModifier::Error;
+            }
+            h
         }
 
         // Highlight references like the definitions they resolve to
+        // `serde` in `extern crate serde;` brings the crate into scope as a module.
+        NAME_REF if element.parent().map(|it| it.kind()) == Some(EXTERN_CRATE_ITEM) => {
+            HighlightTag::Module | HighlightModifier::Definition
+        }
+        NAME_REF if is_crate_root_segment(&element) => {
+            HighlightTag::Module | HighlightModifier::CrateRoot
+        }
         NAME_REF if element.ancestors().any(|it| it.kind() == ATTR) => {
-            Highlight::from(HighlightTag::Function) | HighlightModifier::Attribute
+            // The path of an attribute macro like `#[tokio::main]` resolves to the macro.
+            let name_ref = element.into_node().and_then(ast::NameRef::cast).unwrap();
+            match classify_name_ref(sema, &name_ref) {
+                Some(NameRefClass::Definition(def @ Definition::Macro(_)))
+                | Some(NameRefClass::Definition(
+                    def @ Definition::ModuleDef(hir::ModuleDef::Module(_)),
+                )) => highlight_name(db, def),
+                _ => Highlight::from(HighlightTag::Function) | HighlightModifier::Attribute,
+            }
         }
         NAME_REF => {
             let name_ref = element.into_node().and_then(ast::NameRef::cast).unwrap();
-            match classify_name_ref(sema, &name_ref) {
+            let name_kind = classify_name_ref(sema, &name_ref);
+            let def = match &name_kind {
+                Some(NameRefClass::Definition(def)) => Some(*def),
+                _ => None,
+            };
+            let h = match name_kind {
                 Some(name_kind) => match name_kind {
                     NameRefClass::Definition(def) => {
                         if let Definition::Local(local) = &def {
@@ -376,18 +509,78 @@ fn highlight_element(
                                 binding_hash = Some(calc_binding_hash(&name, *shadow_count))
                             }
                         };
+                        if let Definition::TypeParam(type_param) = &def {
+                            binding_hash = Some(hash(*type_param));
+                        }
                         highlight_name(db, def)
                     }
-                    NameRefClass::FieldShorthand { .. } => HighlightTag::Field.into(),
+                    NameRefClass::FieldShorthand { .. } => {
+                        HighlightTag::Field | HighlightModifier::Shorthand
+                    }
                 },
                 None => HighlightTag::UnresolvedReference.into(),
+            };
+            let mut h = if h.tag == HighlightTag::EnumVariant && is_callee(&name_ref) {
+                h | HighlightModifier::Constructor
+            } else if h.tag == HighlightTag::Function && is_type_param_assoc(sema, &name_ref) {
+                h | HighlightModifier::Associated
+            } else if config.builtin_methods
+                && h.tag == HighlightTag::Function
+                && def.map_or(false, |it| is_builtin_method(db, &it))
+            {
+                h | HighlightModifier::Builtin
+            } else {
+                h
+            };
+            if config.redundant_clone && is_redundant_clone(sema, &name_ref) {
+                h |= HighlightModifier::Redundant;
+            }
+            if config.object_safety && is_non_object_safe_dyn(sema, &name_ref) {
+                h |= HighlightModifier::Error;
+            }
+            if let Some(def) = &def {
+                if is_smart_pointer(db, config, def) {
+                    h |= HighlightModifier::SmartPointer;
+                }
+                if is_marker(db, config, def) {
+                    h |= HighlightModifier::Marker;
+                }
+                if config.drop_types && is_drop_type(db, def) {
+                    h |= HighlightModifier::Drop;
+                }
             }
+            h
         }
 
         // Simple token-based highlighting
         COMMENT => HighlightTag::Comment.into(),
         STRING | RAW_STRING | RAW_BYTE_STRING | BYTE_STRING => HighlightTag::StringLiteral.into(),
         ATTR => HighlightTag::Attribute.into(),
+        // The rest pattern, like `..` in `[first, .., last]`.
+        DOT_DOT_PAT => HighlightTag::Punctuation.into(),
+        T![=] if element.parent().map(|it| it.kind()) == Some(ASSOC_TYPE_ARG) => {
+            HighlightTag::Punctuation.into()
+        }
+        T![->] if is_closure_arrow(&element) => HighlightTag::Punctuation.into(),
+        T![+] | T![-] | T![*] | T![<<] | T![>>] if is_overflowing_const_op(sema, &element) => {
+            HighlightTag::Operator | HighlightModifier::Error
+        }
+        T![*] if is_raw_pointer_token(&element) => {
+            HighlightTag::Operator | HighlightModifier::Unsafe
+        }
+        T![+] if is_dyn_bound_separator(&element) => HighlightTag::Punctuation.into(),
+        T!['{'] | T!['}'] if is_const_arg_brace(&element) => {
+            HighlightTag::Punctuation | HighlightModifier::Const
+        }
+        T![|] if element.parent().map(|it| it.kind()) == Some(OR_PAT) => {
+            HighlightTag::Punctuation.into()
+        }
+        NEVER_TYPE => HighlightTag::BuiltinType.into(),
+        TUPLE_TYPE if is_unit_type(&element) => HighlightTag::BuiltinType.into(),
+        T![&&] if is_condition_connector(&element) => HighlightTag::Operator.into(),
+        INT_NUMBER if is_overflowing_int_literal(sema, &element) => {
+            HighlightTag::NumericLiteral | HighlightModifier::Error
+        }
         INT_NUMBER | FLOAT_NUMBER => HighlightTag::NumericLiteral.into(),
         BYTE => HighlightTag::ByteLiteral.into(),
         CHAR => HighlightTag::CharLiteral.into(),
@@ -402,19 +595,39 @@ fn highlight_element(
         k if k.is_keyword() => {
             let h = Highlight::new(HighlightTag::Keyword);
             match k {
+                T![if] | T![else] => match if_chain_modifier(&element) {
+                    Some(modifier) => h | HighlightModifier::ControlFlow | modifier,
+                    None => h | HighlightModifier::ControlFlow,
+                },
                 T![break]
                 | T![continue]
-                | T![else]
-                | T![if]
                 | T![loop]
                 | T![match]
                 | T![return]
                 | T![while]
                 | T![in] => h | HighlightModifier::ControlFlow,
-                T![for] if !is_child_of_impl(element) => h | HighlightModifier::ControlFlow,
+                T![for] if !is_child_of_impl(&element) => h | HighlightModifier::ControlFlow,
                 T![unsafe] => h | HighlightModifier::Unsafe,
+                T![impl] if is_drop_impl(sema, &element) => h | HighlightModifier::Drop,
+                T![where] => h | HighlightModifier::Constraint,
+                T![const] | T![mut] if is_raw_pointer_token(&element) => {
+                    h | HighlightModifier::Unsafe
+                }
+                // `fn` in `fn(u32) -> bool` names a type, not an item.
+                T![fn] if element.parent().map(|it| it.kind()) == Some(FN_POINTER_TYPE) => {
+                    HighlightTag::BuiltinType.into()
+                }
+                T![try] if element.parent().map(|it| it.kind()) == Some(EFFECT_EXPR) => {
+                    h | HighlightModifier::ControlFlow
+                }
+                T![async] if element.parent().map(|it| it.kind()) == Some(EFFECT_EXPR) => {
+                    h | HighlightModifier::Async
+                }
                 T![true] | T![false] => HighlightTag::BoolLiteral.into(),
                 T![self] => HighlightTag::SelfKeyword.into(),
+                T![crate] if element.parent().map(|it| it.kind()) == Some(PATH_SEGMENT) => {
+                    HighlightTag::Module | HighlightModifier::CrateRoot
+                }
                 _ => h,
             }
         }
@@ -425,19 +638,434 @@ fn highlight_element(
     return Some((highlight, binding_hash));
 
     fn calc_binding_hash(name: &Name, shadow_count: u32) -> u64 {
-        fn hash<T: std::hash::Hash + std::fmt::Debug>(x: T) -> u64 {
-            use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+        hash((name, shadow_count))
+    }
+
+    fn hash<T: std::hash::Hash + std::fmt::Debug>(x: T) -> u64 {
+        use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+}
 
-            let mut hasher = DefaultHasher::new();
-            x.hash(&mut hasher);
-            hasher.finish()
+/// Checks whether the integer literal `element` doesn't fit into its type, like
+/// `256u8`. `usize` and `isize` are assumed to be 64 bits wide.
+fn is_overflowing_int_literal(sema: &Semantics<RootDatabase>, element: &SyntaxElement) -> bool {
+    let literal = match element.parent().and_then(ast::Literal::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let suffix = match literal.kind() {
+        ast::LiteralKind::IntNumber { suffix } => suffix,
+        _ => return false,
+    };
+    let value = int_literal_value(&literal);
+    let int = match suffix.as_ref().and_then(|it| hir::BuiltinInt::from_suffix(it)) {
+        Some(it) => it,
+        // Every integer type fits `i8::MAX`, so only the type of larger
+        // unsuffixed literals has to be inferred.
+        None if value.map_or(false, |it| it <= std::i8::MAX as u128) => return false,
+        None => match sema.type_of_expr(&literal.clone().into()) {
+            Some(ty) => match ty.as_builtin_int() {
+                Some(it) => it,
+                None => return false,
+            },
+            None => return false,
+        },
+    };
+
+    let value = match value {
+        Some(it) => it,
+        None => return true,
+    };
+
+    let bits = int_bits(int);
+    let max = match int.signedness {
+        hir::Signedness::Unsigned => std::u128::MAX >> (128 - bits),
+        hir::Signedness::Signed => {
+            // `-128i8` negates `128i8`, which is allowed even though `128i8` alone isn't.
+            let is_negated = literal
+                .syntax()
+                .parent()
+                .and_then(ast::PrefixExpr::cast)
+                .and_then(|it| it.op_kind())
+                == Some(ast::PrefixOp::Neg);
+            (1 << (bits - 1)) - if is_negated { 0 } else { 1 }
         }
+    };
+    value > max
+}
 
-        hash((name, shadow_count))
+/// Checks whether the arithmetic or shift operator `element` overflows in a
+/// const context, like the `+` in `const X: u8 = 200 + 100;`. Only operands
+/// made of integer literals are evaluated, anything else is never flagged.
+fn is_overflowing_const_op(sema: &Semantics<RootDatabase>, element: &SyntaxElement) -> bool {
+    let bin_expr = match element.parent().and_then(ast::BinExpr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let op = match bin_expr.op_kind() {
+        Some(it) => it,
+        None => return false,
+    };
+    let is_shift = matches!(op, ast::BinOp::LeftShift | ast::BinOp::RightShift);
+    let is_arithmetic =
+        matches!(op, ast::BinOp::Addition | ast::BinOp::Subtraction | ast::BinOp::Multiplication);
+    if !(is_shift || is_arithmetic) {
+        return false;
+    }
+    if !bin_expr.syntax().ancestors().any(|it| matches!(it.kind(), CONST_DEF | STATIC_DEF)) {
+        return false;
+    }
+    let ty = sema.type_of_expr(&bin_expr.clone().into());
+    let int = match ty.and_then(|it| it.as_builtin_int()) {
+        Some(it) => it,
+        None => return false,
+    };
+    // Values are evaluated as `i128`, which can't tell when `u128`s overflow.
+    if int.signedness == hir::Signedness::Unsigned && int_bits(int) == 128 {
+        return false;
+    }
+    let lhs = bin_expr.lhs().and_then(|it| eval_const_int(it, int));
+    let rhs = bin_expr.rhs().and_then(|it| eval_const_int(it, int));
+    let (lhs, rhs) = match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+        _ => return false,
+    };
+    if is_shift {
+        // Shifting out bits is fine, only the shift amount is checked.
+        rhs < 0 || rhs >= i128::from(int_bits(int))
+    } else {
+        eval_int_op(op, lhs, rhs, int).is_none()
     }
 }
 
-fn is_child_of_impl(element: SyntaxElement) -> bool {
+/// Evaluates an integer expression of type `int` made of literals, `+`, `-`
+/// and `*`, like `(1 + 2) * -3`. Returns `None` if some part of it can't be
+/// evaluated or overflows.
+fn eval_const_int(expr: ast::Expr, int: hir::BuiltinInt) -> Option<i128> {
+    match expr {
+        ast::Expr::Literal(it) => match it.kind() {
+            ast::LiteralKind::IntNumber { .. } => {
+                let value = int_literal_value(&it)?;
+                if value > i128::MAX as u128 {
+                    None
+                } else {
+                    Some(value as i128)
+                }
+            }
+            _ => None,
+        },
+        ast::Expr::ParenExpr(it) => eval_const_int(it.expr()?, int),
+        ast::Expr::PrefixExpr(it) if it.op_kind() == Some(ast::PrefixOp::Neg) => {
+            eval_const_int(it.expr()?, int)?.checked_neg()
+        }
+        ast::Expr::BinExpr(it) => {
+            let lhs = eval_const_int(it.lhs()?, int)?;
+            let rhs = eval_const_int(it.rhs()?, int)?;
+            eval_int_op(it.op_kind()?, lhs, rhs, int)
+        }
+        _ => None,
+    }
+}
+
+/// Computes `lhs op rhs` for `+`, `-` and `*`, returning `None` for other
+/// operators or if the result doesn't fit into `int`.
+fn eval_int_op(op: ast::BinOp, lhs: i128, rhs: i128, int: hir::BuiltinInt) -> Option<i128> {
+    let value = match op {
+        ast::BinOp::Addition => lhs.checked_add(rhs)?,
+        ast::BinOp::Subtraction => lhs.checked_sub(rhs)?,
+        ast::BinOp::Multiplication => lhs.checked_mul(rhs)?,
+        _ => return None,
+    };
+    let bits = int_bits(int);
+    let (min, max) = match int.signedness {
+        hir::Signedness::Unsigned => (0, if bits == 128 { i128::MAX } else { (1 << bits) - 1 }),
+        hir::Signedness::Signed => (i128::MIN >> (128 - bits), i128::MAX >> (128 - bits)),
+    };
+    if min <= value && value <= max {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Parses the value of an integer literal, like `0x1_00u8`. Returns `None` if
+/// it doesn't fit into a `u128`.
+fn int_literal_value(literal: &ast::Literal) -> Option<u128> {
+    let suffix = match literal.kind() {
+        ast::LiteralKind::IntNumber { suffix } => suffix,
+        _ => None,
+    };
+    let text = literal.token().text().clone();
+    let digits = text[..text.len() - suffix.map_or(0, |it| it.len())].replace('_', "");
+    let (digits, radix) = match digits.get(..2) {
+        Some("0x") => (&digits[2..], 16),
+        Some("0o") => (&digits[2..], 8),
+        Some("0b") => (&digits[2..], 2),
+        _ => (&digits[..], 10),
+    };
+    u128::from_str_radix(digits, radix).ok()
+}
+
+/// Returns the width of `int`, assuming `usize` and `isize` are 64 bits wide.
+fn int_bits(int: hir::BuiltinInt) -> u32 {
+    match int.bitness {
+        hir::IntBitness::X8 => 8,
+        hir::IntBitness::X16 => 16,
+        hir::IntBitness::X32 => 32,
+        hir::IntBitness::X64 | hir::IntBitness::Xsize => 64,
+        hir::IntBitness::X128 => 128,
+    }
+}
+
+/// Checks whether `element` is the `->` before the return type of a closure.
+fn is_closure_arrow(element: &SyntaxElement) -> bool {
+    element
+        .parent()
+        .filter(|it| it.kind() == RET_TYPE)
+        .and_then(|it| it.parent())
+        .map_or(false, |it| it.kind() == LAMBDA_EXPR)
+}
+
+/// Checks whether `element` is a `+` between the bounds of a trait object, like
+/// in `dyn Error + Send + 'a`.
+fn is_dyn_bound_separator(element: &SyntaxElement) -> bool {
+    element
+        .parent()
+        .filter(|it| it.kind() == TYPE_BOUND_LIST)
+        .and_then(|it| it.parent())
+        .map_or(false, |it| it.kind() == DYN_TRAIT_TYPE)
+}
+
+/// Checks whether `element` is the first segment of a path starting with `::`,
+/// like `std` in `::std::mem::swap`, which names an extern crate.
+fn is_crate_root_segment(element: &SyntaxElement) -> bool {
+    match element.parent().and_then(ast::PathSegment::cast) {
+        Some(segment) => {
+            segment.coloncolon_token().is_some() && segment.parent_path().qualifier().is_none()
+        }
+        None => false,
+    }
+}
+
+/// Checks whether `name_ref` ends the path which is called in a call
+/// expression, like `Some` in `Option::Some(92)`.
+fn is_callee(name_ref: &ast::NameRef) -> bool {
+    // Only the last segment of a path is a child of the outermost `PATH`.
+    let path_expr = match name_ref.syntax().ancestors().nth(3) {
+        Some(it) if it.kind() == PATH_EXPR => it,
+        _ => return false,
+    };
+    path_expr
+        .parent()
+        .and_then(ast::CallExpr::cast)
+        .and_then(|it| it.expr())
+        .map_or(false, |it| it.syntax() == &path_expr)
+}
+
+/// Checks whether `name_ref` is qualified by a type parameter, like `default`
+/// in `T::default()`, which resolves through the trait bounds of `T`.
+fn is_type_param_assoc(sema: &Semantics<RootDatabase>, name_ref: &ast::NameRef) -> bool {
+    let qualifier = name_ref
+        .syntax()
+        .parent()
+        .and_then(ast::PathSegment::cast)
+        .and_then(|it| it.parent_path().qualifier());
+    match qualifier {
+        Some(path) => matches!(sema.resolve_path(&path), Some(hir::PathResolution::TypeParam(_))),
+        None => false,
+    }
+}
+
+/// Checks whether `element` is the `*`, `const` or `mut` of a raw pointer type,
+/// like `*const u8`.
+fn is_raw_pointer_token(element: &SyntaxElement) -> bool {
+    element.parent().map_or(false, |it| it.kind() == POINTER_TYPE)
+}
+
+/// Checks whether `element` is a brace of a block used as a const generic
+/// argument, like in `Array<u8, { N + 1 }>`.
+fn is_const_arg_brace(element: &SyntaxElement) -> bool {
+    element
+        .parent()
+        .filter(|it| it.kind() == BLOCK_EXPR)
+        .and_then(|it| it.parent())
+        .map_or(false, |it| it.kind() == CONST_ARG)
+}
+
+/// Checks whether `element` is the unit type `()`, as opposed to a tuple type
+/// with fields.
+fn is_unit_type(element: &SyntaxElement) -> bool {
+    element
+        .as_node()
+        .cloned()
+        .and_then(ast::TupleType::cast)
+        .map_or(false, |it| it.fields().next().is_none())
+}
+
+/// Checks whether `element` is a `&&` joining the operands of an `if` or
+/// `while` let-chain, like `if let Some(a) = x && let Some(b) = y`. Only `&&`s
+/// reachable from the condition through other `&&`s count, so the one in
+/// `if a || b && c` is not a connector.
+fn is_condition_connector(element: &SyntaxElement) -> bool {
+    element
+        .parent()
+        .and_then(|it| {
+            it.ancestors().find(|it| {
+                ast::BinExpr::cast(it.clone()).and_then(|it| it.op_kind())
+                    != Some(ast::BinOp::BooleanAnd)
+            })
+        })
+        .map_or(false, |it| it.kind() == CONDITION)
+}
+
+/// Checks whether `name_ref` is the method of a `.clone()` call on a `Copy`
+/// type. References are only flagged if everything they point to is `Copy`.
+fn is_redundant_clone(sema: &Semantics<RootDatabase>, name_ref: &ast::NameRef) -> bool {
+    if name_ref.text().as_str() != "clone" {
+        return false;
+    }
+    let call = match name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    if call.arg_list().map_or(false, |it| it.args().next().is_some()) {
+        return false;
+    }
+    let receiver_ty = match call.expr().and_then(|it| sema.type_of_expr(&it)) {
+        Some(it) => it,
+        None => return false,
+    };
+    !receiver_ty.is_unknown() && receiver_ty.autoderef(sema.db).all(|ty| ty.is_copy(sema.db))
+}
+
+/// Checks whether `name_ref` is the trait in a `dyn Trait` type, and the trait
+/// is not object safe.
+fn is_non_object_safe_dyn(sema: &Semantics<RootDatabase>, name_ref: &ast::NameRef) -> bool {
+    let path = match name_ref.syntax().ancestors().find_map(ast::Path::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let in_dyn = path
+        .syntax()
+        .ancestors()
+        .skip_while(|it| it.kind() == PATH || it.kind() == PATH_TYPE)
+        .take_while(|it| it.kind() == TYPE_BOUND || it.kind() == TYPE_BOUND_LIST)
+        .last()
+        .and_then(|it| it.parent())
+        .map_or(false, |it| it.kind() == DYN_TRAIT_TYPE);
+    if !in_dyn {
+        return false;
+    }
+    match sema.resolve_path(&path) {
+        Some(hir::PathResolution::Def(hir::ModuleDef::Trait(trait_))) => {
+            !trait_.is_object_safe(sema.db)
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether `name` has no lowercase letters, like `MAX_LEN`.
+fn is_screaming_case(name: &ast::Name) -> bool {
+    !name.text().chars().any(char::is_lowercase)
+}
+
+/// Checks whether `name` is the name of a trait method with a default body.
+fn is_provided_trait_method(name: &ast::Name) -> bool {
+    let fn_def = match name.syntax().parent().and_then(ast::FnDef::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    fn_def.body().is_some()
+        && fn_def.syntax().ancestors().nth(2).map(|it| it.kind()) == Some(TRAIT_DEF)
+}
+
+/// Checks whether `def` is an ADT listed in `HighlightConfig::smart_pointers`.
+fn is_smart_pointer(db: &RootDatabase, config: &HighlightConfig, def: &Definition) -> bool {
+    if config.smart_pointers.is_empty() {
+        return false;
+    }
+    match def {
+        Definition::ModuleDef(hir::ModuleDef::Adt(adt)) => {
+            let name = adt.name(db).to_string();
+            config.smart_pointers.iter().any(|it| *it == name)
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether `def` is a type listed in `HighlightConfig::marker_types`,
+/// or a struct without fields.
+fn is_marker(db: &RootDatabase, config: &HighlightConfig, def: &Definition) -> bool {
+    if config.marker_types.is_empty() {
+        return false;
+    }
+    match def {
+        Definition::ModuleDef(hir::ModuleDef::Adt(adt)) => {
+            let name = adt.name(db).to_string();
+            config.marker_types.iter().any(|it| *it == name)
+                || matches!(adt, hir::Adt::Struct(it) if it.fields(db).is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether `def` is an ADT implementing `Drop`.
+fn is_drop_type(db: &RootDatabase, def: &Definition) -> bool {
+    match def {
+        Definition::ModuleDef(hir::ModuleDef::Adt(adt)) => adt.ty(db).impls_drop(db),
+        _ => false,
+    }
+}
+
+/// Checks whether `element` is the `impl` keyword of an `impl Drop for T`
+/// block, resolving the trait to the `drop` lang item.
+fn is_drop_impl(sema: &Semantics<RootDatabase>, element: &SyntaxElement) -> bool {
+    element
+        .parent()
+        .and_then(ast::ImplDef::cast)
+        .and_then(|it| sema.to_def(&it))
+        .map_or(false, |it| it.is_drop_impl(sema.db))
+}
+
+/// Checks whether `def` is a method of a builtin type, like `str::len`, that
+/// is a function of an inherent impl for that type.
+fn is_builtin_method(db: &RootDatabase, def: &Definition) -> bool {
+    let func = match def {
+        Definition::ModuleDef(hir::ModuleDef::Function(it)) => it,
+        _ => return false,
+    };
+    match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(hir::AssocItemContainer::ImplDef(impl_def)) => {
+            impl_def.target_trait(db).is_none() && impl_def.target_ty(db).is_builtin()
+        }
+        _ => false,
+    }
+}
+
+fn is_non_exhaustive(db: &RootDatabase, def: impl HasAttrs) -> bool {
+    def.attrs(db).by_key("non_exhaustive").exists()
+}
+
+/// Returns the modifier of an `if` or `else` keyword in an `if`/`else` chain:
+/// `ChainStart` for the first `if` and `ChainMember` for the rest. An `if`
+/// without an `else` is not a chain.
+fn if_chain_modifier(element: &SyntaxElement) -> Option<HighlightModifier> {
+    let if_expr = element.parent().and_then(ast::IfExpr::cast)?;
+    let is_else_if = if_expr.syntax().parent().map(|it| it.kind()) == Some(IF_EXPR);
+    if element.kind() == T![else] || is_else_if {
+        Some(HighlightModifier::ChainMember)
+    } else if if_expr.else_branch().is_some() {
+        Some(HighlightModifier::ChainStart)
+    } else {
+        None
+    }
+}
+
+fn is_child_of_impl(element: &SyntaxElement) -> bool {
     match element.parent() {
         Some(e) => e.kind() == IMPL_DEF,
         _ => false,
@@ -450,14 +1078,52 @@ fn highlight_name(db: &RootDatabase, def: Definition) -> Highlight {
         Definition::Field(_) => HighlightTag::Field,
         Definition::ModuleDef(def) => match def {
             hir::ModuleDef::Module(_) => HighlightTag::Module,
-            hir::ModuleDef::Function(_) => HighlightTag::Function,
-            hir::ModuleDef::Adt(hir::Adt::Struct(_)) => HighlightTag::Struct,
-            hir::ModuleDef::Adt(hir::Adt::Enum(_)) => HighlightTag::Enum,
-            hir::ModuleDef::Adt(hir::Adt::Union(_)) => HighlightTag::Union,
-            hir::ModuleDef::EnumVariant(_) => HighlightTag::EnumVariant,
-            hir::ModuleDef::Const(_) => HighlightTag::Constant,
-            hir::ModuleDef::Trait(_) => HighlightTag::Trait,
-            hir::ModuleDef::TypeAlias(_) => HighlightTag::TypeAlias,
+            hir::ModuleDef::Function(func) => {
+                let mut h = Highlight::new(HighlightTag::Function);
+                if func.is_const(db) {
+                    h |= HighlightModifier::Const;
+                }
+                return h;
+            }
+            hir::ModuleDef::Adt(adt) => {
+                let mut h = Highlight::new(match adt {
+                    hir::Adt::Struct(_) => HighlightTag::Struct,
+                    hir::Adt::Enum(_) => HighlightTag::Enum,
+                    hir::Adt::Union(_) => HighlightTag::Union,
+                });
+                if is_non_exhaustive(db, adt) {
+                    h |= HighlightModifier::NonExhaustive;
+                }
+                return h;
+            }
+            hir::ModuleDef::EnumVariant(variant) => {
+                let mut h = Highlight::new(HighlightTag::EnumVariant);
+                if is_non_exhaustive(db, variant) {
+                    h |= HighlightModifier::NonExhaustive;
+                }
+                return h;
+            }
+            hir::ModuleDef::Const(konst) => {
+                let mut h = Highlight::new(HighlightTag::Constant);
+                if konst.as_assoc_item(db).is_some() {
+                    h |= HighlightModifier::Associated;
+                }
+                return h;
+            }
+            hir::ModuleDef::Trait(trait_) => {
+                let mut h = Highlight::new(HighlightTag::Trait);
+                if trait_.is_unsafe(db) {
+                    h |= HighlightModifier::Unsafe;
+                }
+                return h;
+            }
+            hir::ModuleDef::TypeAlias(type_alias) => {
+                let mut h = Highlight::new(HighlightTag::TypeAlias);
+                if type_alias.as_assoc_item(db).is_some() {
+                    h |= HighlightModifier::Associated;
+                }
+                return h;
+            }
             hir::ModuleDef::BuiltinType(_) => HighlightTag::BuiltinType,
             hir::ModuleDef::Static(s) => {
                 let mut h = Highlight::new(HighlightTag::Static);
@@ -503,6 +1169,7 @@ fn highlight_name_by_syntax(name: ast::Name) -> Highlight {
         STATIC_DEF => HighlightTag::Static,
         ENUM_VARIANT => HighlightTag::EnumVariant,
         BIND_PAT => HighlightTag::Local,
+        MACRO_CALL | MACRO_DEF => HighlightTag::Macro,
         _ => default,
     };
 
@@ -512,6 +1179,7 @@ fn highlight_name_by_syntax(name: ast::Name) -> Highlight {
 fn highlight_injection(
     acc: &mut HighlightedRangeStack,
     sema: &Semantics<RootDatabase>,
+    config: &HighlightConfig,
     literal: ast::RawString,
     expanded: SyntaxToken,
 ) -> Option<()> {
@@ -530,7 +1198,7 @@ fn highlight_injection(
         })
     }
 
-    for mut h in analysis.highlight(tmp_file_id).unwrap() {
+    for mut h in analysis.highlight(tmp_file_id, config).unwrap() {
         if let Some(r) = literal.map_range_up(h.range) {
             h.range = r;
             acc.add(h)
@@ -0,0 +1,77 @@
+//! Finds a few markdown constructs in doc comments, without a full markdown
+//! parser.
+
+use ra_syntax::TextRange;
+
+use super::HighlightModifier;
+
+/// Returns the ranges of the headings, code spans and emphasis in the doc
+/// comment `text`, relative to its start.
+pub(super) fn doc_markdown_ranges(text: &str) -> Vec<(TextRange, HighlightModifier)> {
+    let range =
+        |start: usize, end: usize| TextRange::new((start as u32).into(), (end as u32).into());
+    let mut res = Vec::new();
+    let mut line_start = 0;
+    for (idx, line) in text.split('\n').enumerate() {
+        let offset = line_start + comment_prefix_len(line, idx == 0);
+        line_start += line.len() + 1;
+        let content = &text[offset..line_start - 1];
+        let content = if content.ends_with("*/") { &content[..content.len() - 2] } else { content };
+
+        let trimmed = content.trim_start();
+        let level = trimmed.bytes().take_while(|&it| it == b'#').count();
+        if (1..=6).contains(&level) && (trimmed.len() == level || trimmed[level..].starts_with(' '))
+        {
+            let start = offset + content.len() - trimmed.len();
+            res.push((range(start, offset + content.trim_end().len()), HighlightModifier::Heading));
+            continue;
+        }
+
+        let mut i = 0;
+        while i < content.len() {
+            let marker = match content.as_bytes()[i] {
+                b'`' => "`",
+                b'*' if content[i..].starts_with("**") => "**",
+                b'*' => "*",
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let inner = &content[i + marker.len()..];
+            // A `*` followed by whitespace is a list bullet or a multiplication.
+            let is_open = marker == "`" || inner.starts_with(|c: char| !c.is_whitespace());
+            match inner.find(marker).filter(|&len| is_open && len > 0) {
+                Some(len) => {
+                    let end = i + 2 * marker.len() + len;
+                    let modifier = match marker {
+                        "`" => HighlightModifier::Code,
+                        _ => HighlightModifier::Emphasis,
+                    };
+                    res.push((range(offset + i, offset + end), modifier));
+                    i = end;
+                }
+                None => i += marker.len(),
+            }
+        }
+    }
+    res
+}
+
+/// Returns the length of the comment syntax at the start of `line`, like `///`
+/// or the ` * ` continuing a block comment.
+fn comment_prefix_len(line: &str, is_first: bool) -> usize {
+    if is_first {
+        return ["///", "//!", "/**", "/*!"]
+            .iter()
+            .find(|it| line.starts_with(*it))
+            .map_or(0, |it| it.len());
+    }
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    if trimmed.starts_with('*') && !trimmed.starts_with("*/") {
+        indent + 1
+    } else {
+        indent
+    }
+}
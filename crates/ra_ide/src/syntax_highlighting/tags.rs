@@ -42,6 +42,8 @@ pub enum HighlightTag {
     Local,
     UnresolvedReference,
     FormatSpecifier,
+    Punctuation,
+    Operator,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -56,6 +58,51 @@ pub enum HighlightModifier {
     Definition,
     Mutable,
     Unsafe,
+    /// Used for operations which have no effect, like `.clone()` on a `Copy` type.
+    Redundant,
+    /// Used with `async` keywords introducing a block, which may be suspended.
+    Async,
+    /// Used for trait methods which provide a default body.
+    Provided,
+    /// Used for code which is known to be wrong, like `256u8`.
+    Error,
+    /// Used for constants and types which belong to a trait or an impl.
+    Associated,
+    /// Used for field init shorthands like `x` in `Foo { x }`, which are both a
+    /// field and a local.
+    Shorthand,
+    /// Used for `const fn`s, which can be evaluated at compile time.
+    Const,
+    /// Used for types and variants marked `#[non_exhaustive]`, which other crates
+    /// can not match exhaustively or construct.
+    NonExhaustive,
+    /// Used for types in `HighlightConfig::smart_pointers`, like `Box` or `Rc`.
+    SmartPointer,
+    /// Used for functions returning a `Result` or an `Option`.
+    Fallible,
+    /// Used for tuple variants called like a function, like `Some` in `Some(92)`.
+    Constructor,
+    /// Used for the `where` keyword, which starts the bounds of an item.
+    Constraint,
+    /// Used for path segments naming a crate root, like `crate` in `crate::foo` or
+    /// `std` in `::std::mem`.
+    CrateRoot,
+    /// Used for markdown headings in doc comments, like `# Examples`.
+    Heading,
+    /// Used for markdown code spans in doc comments, like `` `Vec` ``.
+    Code,
+    /// Used for markdown emphasis in doc comments, like `*note*` or `**note**`.
+    Emphasis,
+    /// Used for zero-sized marker types, like `PhantomData` or a unit struct.
+    Marker,
+    /// Used for `impl Drop` blocks and, with `HighlightConfig::drop_types`, types with a destructor.
+    Drop,
+    /// Used for the `if` starting an `if`/`else` chain.
+    ChainStart,
+    /// Used for the `else` and `else if` continuing an `if`/`else` chain.
+    ChainMember,
+    /// Used, with `HighlightConfig::builtin_methods`, for methods of builtin types, like `str::len`.
+    Builtin,
 }
 
 impl HighlightTag {
@@ -89,6 +136,8 @@ impl HighlightTag {
             HighlightTag::Local => "variable",
             HighlightTag::UnresolvedReference => "unresolved_reference",
             HighlightTag::FormatSpecifier => "format_specifier",
+            HighlightTag::Punctuation => "punctuation",
+            HighlightTag::Operator => "operator",
         }
     }
 }
@@ -106,6 +155,27 @@ impl HighlightModifier {
         HighlightModifier::Definition,
         HighlightModifier::Mutable,
         HighlightModifier::Unsafe,
+        HighlightModifier::Redundant,
+        HighlightModifier::Async,
+        HighlightModifier::Provided,
+        HighlightModifier::Error,
+        HighlightModifier::Associated,
+        HighlightModifier::Shorthand,
+        HighlightModifier::Const,
+        HighlightModifier::NonExhaustive,
+        HighlightModifier::SmartPointer,
+        HighlightModifier::Fallible,
+        HighlightModifier::Constructor,
+        HighlightModifier::Constraint,
+        HighlightModifier::CrateRoot,
+        HighlightModifier::Heading,
+        HighlightModifier::Code,
+        HighlightModifier::Emphasis,
+        HighlightModifier::Marker,
+        HighlightModifier::Drop,
+        HighlightModifier::ChainStart,
+        HighlightModifier::ChainMember,
+        HighlightModifier::Builtin,
     ];
 
     fn as_str(self) -> &'static str {
@@ -115,6 +185,27 @@ impl HighlightModifier {
             HighlightModifier::Definition => "declaration",
             HighlightModifier::Mutable => "mutable",
             HighlightModifier::Unsafe => "unsafe",
+            HighlightModifier::Redundant => "redundant",
+            HighlightModifier::Async => "async",
+            HighlightModifier::Provided => "provided",
+            HighlightModifier::Error => "error",
+            HighlightModifier::Associated => "associated",
+            HighlightModifier::Shorthand => "shorthand",
+            HighlightModifier::Const => "const",
+            HighlightModifier::NonExhaustive => "non_exhaustive",
+            HighlightModifier::SmartPointer => "smart_pointer",
+            HighlightModifier::Fallible => "fallible",
+            HighlightModifier::Constructor => "constructor",
+            HighlightModifier::Constraint => "constraint",
+            HighlightModifier::CrateRoot => "crate_root",
+            HighlightModifier::Heading => "heading",
+            HighlightModifier::Code => "code",
+            HighlightModifier::Emphasis => "emphasis",
+            HighlightModifier::Marker => "marker",
+            HighlightModifier::Drop => "drop",
+            HighlightModifier::ChainStart => "chain_start",
+            HighlightModifier::ChainMember => "chain_member",
+            HighlightModifier::Builtin => "builtin",
         }
     }
 
@@ -3,8 +3,8 @@ use std::fs;
 use test_utils::{assert_eq_text, project_dir, read_text};
 
 use crate::{
-    mock_analysis::{single_file, MockAnalysis},
-    FileRange, TextRange,
+    mock_analysis::{analysis_and_position, single_file, single_file_with_position, MockAnalysis},
+    Analysis, FilePosition, FileRange, HighlightConfig, TextRange, TextSize,
 };
 
 #[test]
@@ -128,7 +128,7 @@ fn accidentally_quadratic() {
     let host = mock.analysis_host();
 
     // let t = std::time::Instant::now();
-    let _ = host.analysis().highlight(file_id).unwrap();
+    let _ = host.analysis().highlight(file_id, &HighlightConfig::default()).unwrap();
     // eprintln!("elapsed: {:?}", t.elapsed());
 }
 
@@ -145,7 +145,10 @@ fn test_ranges() {
 
     // The "x"
     let highlights = &analysis
-        .highlight_range(FileRange { file_id, range: TextRange::at(82.into(), 1.into()) })
+        .highlight_range(
+            FileRange { file_id, range: TextRange::at(82.into(), 1.into()) },
+            &HighlightConfig::default(),
+        )
         .unwrap();
 
     assert_eq!(&highlights[0].highlight.to_string(), "field.declaration");
@@ -185,7 +188,7 @@ macro_rules! test {}
 }"#
         .trim(),
     );
-    let _ = analysis.highlight(file_id).unwrap();
+    let _ = analysis.highlight(file_id, &HighlightConfig::default()).unwrap();
 }
 
 #[test]
@@ -257,3 +260,567 @@ fn main() {
     fs::write(dst_file, &actual_html).unwrap();
     assert_eq_text!(expected_html, actual_html);
 }
+
+/// Returns the highlight of the range containing the `<|>` marker.
+fn highlight_at(ra_fixture: &str) -> String {
+    highlight_at_with_config(&HighlightConfig::default(), ra_fixture)
+}
+
+fn highlight_at_with_config(config: &HighlightConfig, ra_fixture: &str) -> String {
+    let (analysis, pos) = single_file_with_position(ra_fixture);
+    highlight_at_position(&analysis, pos, config)
+}
+
+/// Returns the highlight of the range starting at the first occurrence of
+/// `marked` in `text`.
+fn highlight_marked(text: &str, marked: &str) -> String {
+    highlight_marked_with_config(&HighlightConfig::default(), text, marked)
+}
+
+fn highlight_marked_with_config(config: &HighlightConfig, text: &str, marked: &str) -> String {
+    assert!(text.contains(marked), "{:?} not found in {:?}", marked, text);
+    highlight_at_with_config(config, &text.replacen(marked, &format!("<|>{}", marked), 1))
+}
+
+fn highlight_at_position(
+    analysis: &Analysis,
+    pos: FilePosition,
+    config: &HighlightConfig,
+) -> String {
+    let highlights = analysis.highlight(pos.file_id, config).unwrap();
+    highlights
+        .into_iter()
+        .find(|it| it.range.contains(pos.offset))
+        .map(|it| it.highlight.to_string())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_redundant_clone_highlighting() {
+    let text = r#"
+#[lang = "copy"]
+trait Copy {}
+trait Clone { fn clone(&self) -> Self; }
+impl Copy for i32 {}
+impl Clone for i32 { fn clone(&self) -> Self { *self } }
+struct String;
+impl Clone for String { fn clone(&self) -> Self { String } }
+
+fn main() {
+    let some_i32 = 92;
+    let some_string = String;
+    some_i32.clone();
+    some_string.clone();
+}
+"#;
+    let config = HighlightConfig { redundant_clone: true, ..HighlightConfig::default() };
+
+    let actual = highlight_marked_with_config(&config, text, "clone();\n    some_string");
+    assert_eq!(actual, "function.redundant");
+    let actual = highlight_marked_with_config(&config, text, "clone();\n}");
+    assert_eq!(actual, "function");
+    let actual = highlight_marked(text, "clone();\n    some_string");
+    assert_eq!(actual, "function");
+}
+
+#[test]
+fn test_async_block_highlighting() {
+    assert_eq!(highlight_at("fn main() { let f = <|>async { 92 }; }"), "keyword.async");
+    assert_eq!(highlight_at("<|>async fn foo() {}"), "keyword");
+}
+
+#[test]
+fn test_raw_string_delimiters_highlighting() {
+    let (analysis, file_id) = single_file(r###"fn main() { let s = r##"x"##; }"###);
+    let highlights = analysis.highlight(file_id, &HighlightConfig::default()).unwrap();
+    let actual = highlights
+        .iter()
+        .filter(|it| it.range.start() >= TextSize::from(20))
+        .take(3)
+        .map(|it| (it.range, it.highlight.to_string()))
+        .collect::<Vec<_>>();
+    let range = |start: u32, end: u32| TextRange::new(start.into(), end.into());
+    assert_eq!(
+        actual,
+        vec![
+            (range(20, 23), "punctuation".to_string()),
+            (range(23, 26), "string_literal".to_string()),
+            (range(26, 28), "punctuation".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_provided_method_highlighting() {
+    assert_eq!(
+        highlight_at("trait Foo { fn <|>required(); fn provided() {} }"),
+        "function.declaration"
+    );
+    assert_eq!(
+        highlight_at("trait Foo { fn required(); fn <|>provided() {} }"),
+        "function.declaration.provided"
+    );
+    assert_eq!(highlight_at("struct S; impl S { fn <|>inherent() {} }"), "function.declaration");
+}
+
+#[test]
+fn test_let_chain_highlighting() {
+    let text = "fn main() { let x = Some(92); let y = Some(62); \
+                if let Some(a) = x && let Some(b) = y {} }";
+    assert_eq!(highlight_marked(text, "a)"), "variable.declaration");
+    assert_eq!(highlight_marked(text, "b)"), "variable.declaration");
+    assert_eq!(highlight_marked(text, "&&"), "operator");
+
+    let text = "fn main() { let flag = true; while flag && flag && flag {} \
+                if flag || flag && flag {} let b = flag && flag; }";
+    assert_eq!(highlight_marked(text, "&& flag && flag"), "operator");
+    assert_eq!(highlight_marked(text, "&& flag {} let"), "");
+    assert_eq!(highlight_marked(text, "&& flag;"), "");
+}
+
+#[test]
+fn test_never_and_unit_type_highlighting() {
+    assert_eq!(highlight_at("fn f() -> <|>! { loop {} }"), "builtin_type");
+    assert_eq!(highlight_at("fn f() -> <|>() {}"), "builtin_type");
+    assert_eq!(highlight_at("fn f() { let x: <|>() = (); }"), "builtin_type");
+    assert_eq!(highlight_at("fn f() { let x = <|>(); }"), "");
+}
+
+#[test]
+fn test_attribute_macro_highlighting() {
+    let fixture = |attr: &str| {
+        format!(
+            r#"
+//- /main.rs
+#[{}]
+fn main() {{}}
+
+//- /tokio/lib.rs
+#[macro_export]
+macro_rules! main {{ () => {{}} }}
+"#,
+            attr
+        )
+    };
+    let highlight = |attr: &str| {
+        let (analysis, pos) = analysis_and_position(&fixture(attr));
+        highlight_at_position(&analysis, pos, &HighlightConfig::default())
+    };
+    assert_eq!(highlight("tokio::<|>main"), "macro");
+    assert_eq!(highlight("<|>tokio::main"), "module");
+    assert_eq!(highlight("tokio::main<|>"), "attribute");
+    assert_eq!(highlight("<|>inline"), "function.attribute");
+}
+
+#[test]
+fn test_overflowing_literal_highlighting() {
+    assert_eq!(highlight_at("fn main() { <|>256u8; }"), "numeric_literal.error");
+    assert_eq!(highlight_at("fn main() { <|>300i8; }"), "numeric_literal.error");
+    assert_eq!(highlight_at("fn main() { let x: u8 = <|>0x1_00; }"), "numeric_literal.error");
+    assert_eq!(highlight_at("fn main() { <|>255u8; }"), "numeric_literal");
+    assert_eq!(highlight_at("fn main() { -<|>128i8; }"), "numeric_literal");
+    assert_eq!(highlight_at("fn main() { let x: i8 = <|>127; }"), "numeric_literal");
+    assert_eq!(highlight_at("fn main() { let x: i8 = <|>128; }"), "numeric_literal.error");
+    assert_eq!(highlight_at("fn main() { let x: u8 = <|>128; }"), "numeric_literal");
+}
+
+#[test]
+fn test_overflowing_const_op_highlighting() {
+    assert_eq!(highlight_at("const X: u8 = 200 <|>+ 100;"), "operator.error");
+    assert_eq!(highlight_at("const X: i8 = -100 <|>- (20 + 9);"), "operator.error");
+    assert_eq!(highlight_at("static X: u32 = 1 <|><< 32;"), "operator.error");
+    assert_eq!(highlight_at("const X: u8 = 200 <|>+ 55;"), "");
+    assert_eq!(highlight_at("const X: u8 = 255 <|><< 1;"), "");
+    assert_eq!(highlight_at("const X: u8 = (200 + 100) <|>- 100;"), "");
+    assert_eq!(highlight_at("fn main() { let x: u8 = 200 <|>+ 100; }"), "");
+}
+
+#[test]
+fn test_associated_item_highlighting() {
+    let text = "trait Tr { const C: i32; type Item; }\nstruct S;\n\
+                impl Tr for S { const C: i32 = 92; type Item = i32; }\n\
+                const FREE: i32 = 0;\ntype Free = i32;\nfn f(x: Free) { S::C; FREE; }";
+    assert_eq!(highlight_at("trait Tr { const <|>C: i32; }"), "constant.declaration.associated");
+    assert_eq!(highlight_at("trait Tr { type <|>Item; }"), "type_alias.declaration.associated");
+    assert_eq!(highlight_marked(text, "C; FREE"), "constant.associated");
+    assert_eq!(highlight_marked(text, "FREE;"), "constant");
+    assert_eq!(highlight_marked(text, "Free)"), "type_alias");
+}
+
+#[test]
+fn test_slice_pattern_highlighting() {
+    let text = "fn f(arr: [i32; 4]) { let [first, .., last] = arr; let [head, rest @ ..] = arr; }";
+    assert_eq!(highlight_marked(text, "first"), "variable.declaration");
+    assert_eq!(highlight_marked(text, ".., last"), "punctuation");
+    assert_eq!(highlight_marked(text, "last]"), "variable.declaration");
+    assert_eq!(highlight_marked(text, "rest @"), "variable.declaration");
+    assert_eq!(highlight_marked(text, "..]"), "punctuation");
+    assert_eq!(highlight_at("fn f() { let r = 0<|>..1; }"), "");
+}
+
+#[test]
+fn test_assoc_type_binding_highlighting() {
+    let text = "trait Iterator { type Item; }\nfn f<T>() where T: Iterator<Item = u32> {}";
+    assert_eq!(highlight_marked(text, "Item = u32"), "type_alias.associated");
+    assert_eq!(highlight_marked(text, "= u32"), "punctuation");
+    assert_eq!(highlight_marked(text, "Iterator<"), "trait");
+}
+
+#[test]
+fn test_closure_signature_highlighting() {
+    let text = "fn main() { let f = |x: u32| -> bool { x > 0 }; }";
+    assert_eq!(highlight_marked(text, "x: u32"), "variable.declaration");
+    assert_eq!(highlight_marked(text, "u32|"), "builtin_type");
+    assert_eq!(highlight_marked(text, "-> bool"), "punctuation");
+    assert_eq!(highlight_marked(text, "bool {"), "builtin_type");
+    assert_eq!(highlight_at("fn f() <|>-> bool { true }"), "");
+}
+
+#[test]
+fn test_object_safety_highlighting() {
+    let config = HighlightConfig { object_safety: true, ..HighlightConfig::default() };
+    let text = r#"
+trait Safe { fn f(&self); fn g() where Self: Sized; }
+trait Generic { fn f<T>(&self, x: T); }
+trait Constructor { fn new() -> Self; }
+fn f(a: &dyn Generic, b: &dyn Constructor, c: &dyn Safe, d: &impl Generic) {}
+"#;
+    assert_eq!(highlight_marked_with_config(&config, text, "Generic, b"), "trait.error");
+    assert_eq!(highlight_marked_with_config(&config, text, "Constructor,"), "trait.error");
+    assert_eq!(highlight_marked_with_config(&config, text, "Safe,"), "trait");
+    assert_eq!(highlight_marked_with_config(&config, text, "Generic)"), "trait");
+    assert_eq!(highlight_marked(text, "Generic, b"), "trait");
+}
+
+#[test]
+fn test_macro_definition_highlighting() {
+    assert_eq!(highlight_at("macro_rules! <|>my_macro { () => {} }"), "macro.declaration");
+    assert_eq!(highlight_at("macro <|>m($i:ident) {}"), "macro.declaration");
+    assert_eq!(highlight_at("macro <|>m { ($i:ident) => {} }"), "macro.declaration");
+}
+
+#[test]
+fn test_field_shorthand_highlighting() {
+    let text = "struct Foo { x: i32 }\nfn main() { let x = 92; Foo { x }; Foo { x: x }; }";
+    assert_eq!(highlight_marked(text, "x }; Foo"), "field.shorthand");
+    assert_eq!(highlight_marked(text, "x: x"), "field");
+}
+
+#[test]
+fn test_const_fn_highlighting() {
+    let text = "const fn answer() -> i32 { 92 }\nfn main() { answer(); main(); }";
+    assert_eq!(highlight_marked(text, "answer()"), "function.declaration.const");
+    assert_eq!(highlight_marked(text, "answer();"), "function.const");
+    assert_eq!(highlight_marked(text, "main()"), "function.declaration");
+    assert_eq!(highlight_marked(text, "main();"), "function");
+}
+
+#[test]
+fn test_raw_pointer_highlighting() {
+    let text = "fn f(p: *const u8, q: *mut u8) { let r = &mut 92; *r = 0; }";
+    assert_eq!(highlight_marked(text, "*const"), "operator.unsafe");
+    assert_eq!(highlight_marked(text, "const"), "keyword.unsafe");
+    assert_eq!(highlight_marked(text, "*mut"), "operator.unsafe");
+    assert_eq!(highlight_marked(text, "mut u8"), "keyword.unsafe");
+    assert_eq!(highlight_marked(text, "mut 92"), "keyword");
+    assert_eq!(highlight_marked(text, "*r"), "");
+}
+
+#[test]
+fn test_enum_variant_cast_highlighting() {
+    let text = "enum Color { Red, Green }\nfn main() { let x = Color::Red as u32; }";
+    assert_eq!(highlight_marked(text, "Red as"), "enum_variant");
+    assert_eq!(highlight_marked(text, "Color::"), "enum");
+    assert_eq!(highlight_marked(text, "u32;"), "builtin_type");
+}
+
+#[test]
+fn test_dyn_bounds_highlighting() {
+    let text = "trait Error {}\ntrait Tag {}\n\
+                fn f<'a, T: Error + Tag>(e: Box<dyn Error + Tag + 'a>) {}";
+    assert_eq!(highlight_marked(text, "Error + Tag +"), "trait");
+    assert_eq!(highlight_marked(text, "+ Tag +"), "punctuation");
+    assert_eq!(highlight_marked(text, "+ 'a"), "punctuation");
+    assert_eq!(highlight_marked(text, "'a>"), "lifetime");
+    assert_eq!(highlight_marked(text, "+ Tag>"), "");
+}
+
+#[test]
+fn test_impl_header_type_param_highlighting() {
+    let text = "trait Bound {}\ntrait Trait<T> {}\nstruct Struct<T>(T);\n\
+                impl<T: Bound> Trait<T> for Struct<T> {}";
+    assert_eq!(highlight_marked(text, "T: Bound"), "type_param.declaration");
+    assert_eq!(highlight_marked(text, "T> for"), "type_param");
+    assert_eq!(highlight_marked(text, "T> {}"), "type_param");
+}
+
+#[test]
+fn test_try_block_highlighting() {
+    let text = "fn foo() -> Option<i32> { None }\n\
+                fn main() { let x: Option<i32> = try { foo()? }; }";
+    assert_eq!(highlight_marked(text, "try"), "keyword.control");
+    assert_eq!(highlight_marked(text, "foo()?"), "function");
+}
+
+#[test]
+fn test_const_arg_highlighting() {
+    let text = "struct Array<T, const N: usize>([T; N]);\n\
+                fn main() { let a: Array<u8, { 2 + 1 }>; }";
+    assert_eq!(highlight_marked(text, "{ 2"), "punctuation.const");
+    assert_eq!(highlight_marked(text, "}>"), "punctuation.const");
+    assert_eq!(highlight_marked(text, "2 +"), "numeric_literal");
+    assert_eq!(highlight_marked(text, "{ let"), "");
+}
+
+#[test]
+fn test_or_pattern_highlighting() {
+    let text = "fn main() {\n\
+                let x = match Some(1) { Some(1) | Some(2) => 1, _ => 0 };\n\
+                let y = x | 2;\n\
+                }";
+    assert_eq!(highlight_marked(text, "| Some(2)"), "punctuation");
+    assert_eq!(highlight_marked(text, "| 2"), "");
+}
+
+#[test]
+fn test_non_exhaustive_highlighting() {
+    let text = "#[non_exhaustive]\nenum Error { Io, #[non_exhaustive] Parse }\n\
+                enum Plain { A }\n\
+                fn main() { let e = Error::Io; let p = Error::Parse; let a = Plain::A; }";
+    assert_eq!(highlight_marked(text, "Error {"), "enum.declaration.non_exhaustive");
+    assert_eq!(highlight_marked(text, "Error::Io"), "enum.non_exhaustive");
+    assert_eq!(highlight_marked(text, "Io;"), "enum_variant");
+    assert_eq!(highlight_marked(text, "Parse;"), "enum_variant.non_exhaustive");
+    assert_eq!(highlight_marked(text, "Plain::"), "enum");
+}
+
+#[test]
+fn test_fn_pointer_highlighting() {
+    let text = "fn is_even(x: u32) -> bool { x % 2 == 0 }\n\
+                fn main() { let f: fn(u32) -> bool = is_even; }";
+    assert_eq!(highlight_marked(text, "fn is_even"), "keyword");
+    assert_eq!(highlight_marked(text, "fn(u32)"), "builtin_type");
+    assert_eq!(highlight_marked(text, "u32) ->"), "builtin_type");
+    assert_eq!(highlight_marked(text, "bool ="), "builtin_type");
+}
+
+#[test]
+fn test_smart_pointer_highlighting() {
+    let text = "struct Box<T>(T);\nstruct Rc<T>(T);\nfn f(x: Box<u32>, y: Rc<u32>) {}";
+    let config =
+        HighlightConfig { smart_pointers: vec!["Box".to_string()], ..HighlightConfig::default() };
+    assert_eq!(highlight_marked_with_config(&config, text, "Box<u32>"), "struct.smart_pointer");
+    assert_eq!(highlight_marked_with_config(&config, text, "Rc<u32>"), "struct");
+    assert_eq!(highlight_marked(text, "Box<u32>"), "struct");
+}
+
+#[test]
+fn test_fallible_function_highlighting() {
+    let fixture = r#"
+//- /main.rs
+use std::result::Result;
+type Res<T> = Result<T, ()>;
+enum Option<T> { None }
+fn parse() -> Result<(), ()> { Result::Ok(()) }
+fn alias() -> Res<()> { Result::Ok(()) }
+fn shadowed() -> Option<()> { Option::None }
+fn run() -> () {}
+
+//- /std/lib.rs
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#;
+    let highlight = |config: &HighlightConfig, marked: &str| {
+        let fixture = fixture.replacen(marked, &format!("<|>{}", marked), 1);
+        let (analysis, pos) = analysis_and_position(&fixture);
+        highlight_at_position(&analysis, pos, config)
+    };
+    let config = HighlightConfig { fallible_functions: true, ..HighlightConfig::default() };
+    assert_eq!(highlight(&config, "parse"), "function.declaration.fallible");
+    assert_eq!(highlight(&config, "alias"), "function.declaration.fallible");
+    assert_eq!(highlight(&config, "shadowed"), "function.declaration");
+    assert_eq!(highlight(&config, "run"), "function.declaration");
+    assert_eq!(highlight(&HighlightConfig::default(), "parse"), "function.declaration");
+}
+
+#[test]
+fn test_variant_constructor_highlighting() {
+    let text = "enum Option<T> { Some(T), None }\n\
+                fn main() { let a = Option::Some(1); let b: Option<i32> = Option::None; \
+                let c = Option::Some; }";
+    assert_eq!(highlight_marked(text, "Some(1)"), "enum_variant.constructor");
+    assert_eq!(highlight_marked(text, "Option::Some(1)"), "enum");
+    assert_eq!(highlight_marked(text, "None;"), "enum_variant");
+    assert_eq!(highlight_marked(text, "Some; }"), "enum_variant");
+    assert_eq!(highlight_marked(text, "Some(T)"), "enum_variant.declaration");
+}
+
+#[test]
+fn test_where_keyword_highlighting() {
+    let text = "trait Bound {}\nfn f<T>(x: T) where T: Bound {}";
+    assert_eq!(highlight_marked(text, "where"), "keyword.constraint");
+    assert_eq!(highlight_marked(text, "T: Bound"), "type_param");
+    assert_eq!(highlight_marked(text, "fn f"), "keyword");
+}
+
+#[test]
+fn test_type_param_binding_hash() {
+    let text = "fn f<T>(x: T) -> T { let y: T = x; y }\nfn g<T>(x: T) {}";
+    let (analysis, file_id) = single_file(text);
+    let highlights = analysis.highlight(file_id, &HighlightConfig::default()).unwrap();
+    let hashes = highlights
+        .iter()
+        .filter(|it| &text[it.range] == "T")
+        .map(|it| it.binding_hash)
+        .collect::<Vec<_>>();
+    assert_eq!(hashes.len(), 6);
+    assert!(hashes[0].is_some());
+    assert!(hashes[..4].iter().all(|it| *it == hashes[0]));
+    assert!(hashes[4].is_some());
+    assert_eq!(hashes[4], hashes[5]);
+    assert_ne!(hashes[0], hashes[4]);
+}
+
+#[test]
+fn test_naming_case_highlighting() {
+    let text = "const foo: u32 = 1;\nconst FOO_2: u32 = 2;\nstatic bar: u32 = 3;";
+    let config = HighlightConfig { naming_case: true, ..HighlightConfig::default() };
+    assert_eq!(highlight_marked_with_config(&config, text, "foo"), "constant.declaration.error");
+    assert_eq!(highlight_marked_with_config(&config, text, "FOO_2"), "constant.declaration");
+    assert_eq!(highlight_marked_with_config(&config, text, "bar"), "static.declaration.error");
+    assert_eq!(highlight_marked(text, "foo"), "constant.declaration");
+}
+
+#[test]
+fn test_unsafe_trait_highlighting() {
+    let text = "unsafe trait Send {}\ntrait Sync {}\nstruct MyType;\n\
+                unsafe impl Send for MyType {}\nimpl Sync for MyType {}";
+    assert_eq!(highlight_marked(text, "Send {}"), "trait.declaration.unsafe");
+    assert_eq!(highlight_marked(text, "Send for"), "trait.unsafe");
+    assert_eq!(highlight_marked(text, "unsafe impl"), "keyword.unsafe");
+    assert_eq!(highlight_marked(text, "Sync for"), "trait");
+}
+
+#[test]
+fn test_crate_root_highlighting() {
+    let text = "extern crate serde;\nmod foo {}\n\
+                fn main() { ::std::mem::swap(&mut 1, &mut 2); crate::foo; }";
+    assert_eq!(highlight_marked(text, "serde"), "module.declaration");
+    assert_eq!(highlight_marked(text, "std"), "module.crate_root");
+    assert_eq!(highlight_marked(text, "crate::"), "module.crate_root");
+    assert_eq!(highlight_marked(text, "mem"), "unresolved_reference");
+    assert_eq!(highlight_marked(text, "extern"), "keyword");
+}
+
+#[test]
+fn test_macro_bang_and_never_type_highlighting() {
+    let text = "fn f() -> ! { loop {} }\nfn main() { println!(\"Hello\"); }";
+    let (analysis, file_id) = single_file(text);
+    let highlights = analysis.highlight(file_id, &HighlightConfig::default()).unwrap();
+    let highlights = highlights
+        .iter()
+        .filter(|it| matches!(&text[it.range], "!" | "println"))
+        .map(|it| (&text[it.range], it.highlight.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        highlights,
+        vec![
+            ("!", "builtin_type".to_string()),
+            ("println", "macro".to_string()),
+            ("!", "punctuation".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_type_param_assoc_fn_highlighting() {
+    let text = "trait Default { fn default() -> Self; }\n\
+                fn make<T: Default>() -> T { T::default() }\n\
+                struct S;\nimpl S { fn new() -> S { S } }\nfn f() { S::new(); }";
+    assert_eq!(highlight_marked(text, "default() }"), "function.associated");
+    assert_eq!(highlight_marked(text, "T::"), "type_param");
+    assert_eq!(highlight_marked(text, "new();"), "function");
+}
+
+#[test]
+fn test_doc_markdown_highlighting() {
+    let text = "/// # Examples\n/// Calls `foo` with **all** items.\nfn foo() {}\n\
+                /** Uses `bar`\n * ## Panics\n */\nfn bar() {}\n// Not a `doc` comment";
+    let config = HighlightConfig { doc_markdown: true, ..HighlightConfig::default() };
+    assert_eq!(highlight_marked_with_config(&config, text, "# Examples"), "comment.heading");
+    assert_eq!(highlight_marked_with_config(&config, text, "`foo`"), "comment.code");
+    assert_eq!(highlight_marked_with_config(&config, text, "**all**"), "comment.emphasis");
+    assert_eq!(highlight_marked_with_config(&config, text, "Calls"), "comment");
+    assert_eq!(highlight_marked_with_config(&config, text, "`bar`"), "comment.code");
+    assert_eq!(highlight_marked_with_config(&config, text, "## Panics"), "comment.heading");
+    assert_eq!(highlight_marked_with_config(&config, text, "`doc`"), "comment");
+    assert_eq!(highlight_marked(text, "`foo`"), "comment");
+}
+
+#[test]
+fn test_marker_highlighting() {
+    let text = "struct PhantomData<T>;\nstruct Pinned(u8);\nstruct Tag;\nstruct Id(u32);\n\
+                struct Foo<T> { marker: PhantomData<T>, pinned: Pinned, tag: Tag, id: Id }";
+    let config = HighlightConfig {
+        marker_types: vec!["PhantomData".to_string(), "Pinned".to_string()],
+        ..HighlightConfig::default()
+    };
+    assert_eq!(highlight_marked_with_config(&config, text, "PhantomData<T>,"), "struct.marker");
+    assert_eq!(highlight_marked_with_config(&config, text, "Pinned,"), "struct.marker");
+    assert_eq!(highlight_marked_with_config(&config, text, "Tag }"), "struct.marker");
+    assert_eq!(highlight_marked_with_config(&config, text, "Id }"), "struct");
+    assert_eq!(highlight_marked(text, "PhantomData<T>,"), "struct");
+}
+
+#[test]
+fn test_drop_highlighting() {
+    let text = "#[lang = \"drop\"]\ntrait Drop { fn drop(&mut self); }\n\
+                struct Guard;\nstruct Plain;\n\
+                impl Drop for Guard { fn drop(&mut self) {} }\nimpl Plain {}\n\
+                fn f(guard: Guard, plain: Plain) {}";
+    assert_eq!(highlight_marked(text, "impl Drop"), "keyword.drop");
+    assert_eq!(highlight_marked(text, "impl Plain"), "keyword");
+
+    let config = HighlightConfig { drop_types: true, ..HighlightConfig::default() };
+    assert_eq!(highlight_marked_with_config(&config, text, "Guard, plain"), "struct.drop");
+    assert_eq!(highlight_marked_with_config(&config, text, "Plain) {}"), "struct");
+    assert_eq!(highlight_marked(text, "Guard, plain"), "struct");
+
+    let config = HighlightConfig {
+        drop_types: true,
+        smart_pointers: vec!["Guard".to_string()],
+        ..HighlightConfig::default()
+    };
+    assert_eq!(
+        highlight_marked_with_config(&config, text, "Guard, plain"),
+        "struct.smart_pointer.drop"
+    );
+
+    let text = "mod ops { pub trait Drop {} }\nstruct Guard;\nimpl ops::Drop for Guard {}";
+    assert_eq!(highlight_marked(text, "impl ops::Drop"), "keyword");
+}
+
+#[test]
+fn test_if_chain_highlighting() {
+    let text = "fn f(x: u32) {\n    if x == 0 {\n    } else if x == 1 {\n    } else {\n    }\n\
+                \x20   if x == 2 {}\n}";
+    assert_eq!(highlight_marked(text, "if x == 0"), "keyword.control.chain_start");
+    assert_eq!(highlight_marked(text, "else if"), "keyword.control.chain_member");
+    assert_eq!(highlight_marked(text, "if x == 1"), "keyword.control.chain_member");
+    assert_eq!(highlight_marked(text, "else {"), "keyword.control.chain_member");
+    assert_eq!(highlight_marked(text, "if x == 2"), "keyword.control");
+}
+
+#[test]
+fn test_builtin_method_highlighting() {
+    let text = "#[lang = \"str\"]\nimpl str { fn len(&self) -> usize { 0 } }\n\
+                struct S;\nimpl S { fn len(&self) -> usize { 0 } }\n\
+                fn f() { \"s\".len(); S.len(); str::len(\"s\"); }";
+    assert_eq!(highlight_marked(text, "len(); S"), "function");
+
+    let config = HighlightConfig { builtin_methods: true, ..HighlightConfig::default() };
+    assert_eq!(highlight_marked_with_config(&config, text, "len(); S"), "function.builtin");
+    assert_eq!(highlight_marked_with_config(&config, text, "len(); str"), "function");
+    assert_eq!(highlight_marked_with_config(&config, text, "len(\"s\")"), "function.builtin");
+}
@@ -18,7 +18,7 @@ use ra_syntax::{
 use crate::RootDatabase;
 
 // FIXME: a more precise name would probably be `Symbol`?
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Definition {
     Macro(MacroDef),
     Field(Field),
@@ -241,6 +241,26 @@ pub fn classify_name_ref(
         }
     }
 
+    if let Some(assoc_type_arg) = ast::AssocTypeArg::cast(parent.clone()) {
+        // `Item` in `Iterator<Item = u32>` refers to the associated type of the trait.
+        if let Some(path) = assoc_type_arg.syntax().ancestors().find_map(ast::Path::cast) {
+            if let Some(PathResolution::Def(ModuleDef::Trait(trait_))) = sema.resolve_path(&path) {
+                let type_alias = trait_.items(sema.db).into_iter().find_map(|item| match item {
+                    hir::AssocItem::TypeAlias(it)
+                        if it.name(sema.db).to_string() == name_ref.text().as_str() =>
+                    {
+                        Some(it)
+                    }
+                    _ => None,
+                });
+                if let Some(type_alias) = type_alias {
+                    let def = Definition::ModuleDef(type_alias.into());
+                    return Some(NameRefClass::Definition(def));
+                }
+            }
+        }
+    }
+
     if let Some(macro_call) = parent.ancestors().find_map(ast::MacroCall::cast) {
         if let Some(macro_def) = sema.resolve_macro_call(&macro_call) {
             return Some(NameRefClass::Definition(Definition::Macro(macro_def)));
@@ -1,6 +1,6 @@
 //! `LineIndex` maps flat `TextSize` offsets into `(Line, Column)`
 //! representation.
-use std::iter;
+use std::{iter, ops::RangeInclusive};
 
 use ra_syntax::{TextRange, TextSize};
 use rustc_hash::FxHashMap;
@@ -12,6 +12,8 @@ pub struct LineIndex {
     pub(crate) newlines: Vec<TextSize>,
     /// List of non-ASCII characters on each line
     pub(crate) utf16_lines: FxHashMap<u32, Vec<Utf16Char>>,
+    /// Length of the whole text
+    pub(crate) len: TextSize,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -85,7 +87,94 @@ impl LineIndex {
             utf16_lines.insert(line, utf16_chars);
         }
 
-        LineIndex { newlines, utf16_lines }
+        LineIndex { newlines, utf16_lines, len: curr_row }
+    }
+
+    /// Builds an index of `text` from the precomputed offsets of its line
+    /// starts, beginning with `0`. The offsets are trusted to match `text`, so
+    /// only the UTF-16 columns are computed.
+    pub fn from_parts(newlines: Vec<TextSize>, text: &str) -> LineIndex {
+        let len = TextSize::of(text);
+        debug_assert_eq!(newlines.first(), Some(&0.into()));
+        debug_assert!(newlines.windows(2).all(|it| it[0] < it[1]));
+        debug_assert!(newlines.iter().all(|&it| it <= len));
+
+        let mut utf16_lines = FxHashMap::default();
+        for (line, &start) in newlines.iter().enumerate() {
+            let end = newlines.get(line + 1).copied().unwrap_or(len);
+            let utf16_chars = text[TextRange::new(start, end)]
+                .char_indices()
+                .filter(|(_, c)| !c.is_ascii())
+                .map(|(idx, c)| {
+                    let start = TextSize::from(idx as u32);
+                    Utf16Char { start, end: start + TextSize::of(c) }
+                })
+                .collect::<Vec<_>>();
+            if !utf16_chars.is_empty() {
+                utf16_lines.insert(line as u32, utf16_chars);
+            }
+        }
+
+        LineIndex { newlines, utf16_lines, len }
+    }
+
+    /// Builds an index of `bytes`, which need not be valid UTF-8. No
+    /// characters are collected, so UTF-16 columns are unavailable: the
+    /// `col_utf16` of a `LineCol` is the byte column instead.
+    pub fn from_bytes(bytes: &[u8]) -> LineIndex {
+        let newlines = iter::once(0.into())
+            .chain(
+                bytes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &b)| b == b'\n')
+                    .map(|(idx, _)| TextSize::from(idx as u32 + 1)),
+            )
+            .collect();
+        LineIndex {
+            newlines,
+            utf16_lines: FxHashMap::default(),
+            len: TextSize::from(bytes.len() as u32),
+        }
+    }
+
+    /// Updates the index for the deletion of `range` from its text, without
+    /// rescanning the text. The lines touched by `range` are joined into one.
+    pub fn delete_range(&mut self, range: TextRange) {
+        let (first, start_col) = self.line_and_byte_col(range.start());
+        let (last, end_col) = self.line_and_byte_col(range.end());
+        let (start_col, end_col) = (TextSize::from(start_col), TextSize::from(end_col));
+
+        self.newlines.drain(first as usize + 1..=last as usize);
+        for it in self.newlines[first as usize + 1..].iter_mut() {
+            *it -= range.len();
+        }
+        self.len -= range.len();
+
+        // Join the characters before `range` on the first line with those
+        // after it on the last line, and renumber the lines after that.
+        let first_chars = self.utf16_lines.remove(&first).unwrap_or_default();
+        let last_chars = if last == first {
+            first_chars.clone()
+        } else {
+            self.utf16_lines.remove(&last).unwrap_or_default()
+        };
+        let mut chars =
+            first_chars.into_iter().filter(|it| it.end <= start_col).collect::<Vec<_>>();
+        chars.extend(last_chars.into_iter().filter(|it| it.start >= end_col).map(|it| Utf16Char {
+            start: it.start - end_col + start_col,
+            end: it.end - end_col + start_col,
+        }));
+        let mut utf16_lines = self
+            .utf16_lines
+            .drain()
+            .filter(|&(line, _)| line < first || line > last)
+            .map(|(line, chars)| (if line > last { line - (last - first) } else { line }, chars))
+            .collect::<FxHashMap<_, _>>();
+        if !chars.is_empty() {
+            utf16_lines.insert(first, chars);
+        }
+        self.utf16_lines = utf16_lines;
     }
 
     pub fn line_col(&self, offset: TextSize) -> LineCol {
@@ -96,12 +185,115 @@ impl LineIndex {
         LineCol { line: line as u32, col_utf16: self.utf8_to_utf16_col(line as u32, col) as u32 }
     }
 
+    /// Returns the line of `offset` and its UTF-8 column, which unlike
+    /// `line_col` needs no UTF-16 correction.
+    pub fn line_and_byte_col(&self, offset: TextSize) -> (u32, u32) {
+        let line = self.newlines.upper_bound(&offset) - 1;
+        (line as u32, (offset - self.newlines[line]).into())
+    }
+
     pub fn offset(&self, line_col: LineCol) -> TextSize {
         //FIXME: return Result
         let col = self.utf16_to_utf8_col(line_col.line, line_col.col_utf16);
         self.newlines[line_col.line as usize] + col
     }
 
+    /// Converts many positions at once, like calling `offset` for each of
+    /// them, and returns the offsets in the order of `positions`. The
+    /// positions are processed grouped by line, and those on lines past the
+    /// end of the text map to `None`.
+    pub fn offsets(&self, positions: &[LineCol]) -> Vec<Option<TextSize>> {
+        let mut order = (0..positions.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&idx| positions[idx].line);
+
+        let mut res = vec![None; positions.len()];
+        for idx in order {
+            let line_col = positions[idx];
+            if let Some(&line_start) = self.newlines.get(line_col.line as usize) {
+                res[idx] =
+                    Some(line_start + self.utf16_to_utf8_col(line_col.line, line_col.col_utf16));
+            }
+        }
+        res
+    }
+
+    /// Returns the UTF-16 length of `line`, not counting its newline.
+    pub fn line_end_col_utf16(&self, line: u32, text: &str) -> Option<u32> {
+        let start = *self.newlines.get(line as usize)?;
+        let end = self.newlines.get(line as usize + 1).copied().unwrap_or(self.len);
+        let line_text = text.get(usize::from(start)..usize::from(end))?;
+        Some(line_text.trim_end_matches('\n').encode_utf16().count() as u32)
+    }
+
+    /// Moves `pos` by `delta` lines, stopping at the first and the last line.
+    /// The column is clamped to the length of the line it lands on.
+    pub fn move_vertically(&self, pos: LineCol, delta: i32, text: &str) -> LineCol {
+        let last_line = self.newlines.len() as i64 - 1;
+        let line = (i64::from(pos.line) + i64::from(delta)).max(0).min(last_line) as u32;
+        let col_utf16 = self.line_end_col_utf16(line, text).map_or(0, |len| pos.col_utf16.min(len));
+        LineCol { line, col_utf16 }
+    }
+
+    /// Returns the range of the identifier-like word containing `offset`, which
+    /// may also be at the end of the word. Returns `None` if there is no word
+    /// around `offset`.
+    pub fn word_range_at(&self, offset: TextSize, text: &str) -> Option<TextRange> {
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let line = self.line_col(offset).line as usize;
+        let line_start = usize::from(self.newlines[line]);
+        let offset = usize::from(offset);
+        let before = text.get(line_start..offset)?;
+        let after = text.get(offset..)?;
+
+        let start = before.trim_end_matches(is_word_char).len() + line_start;
+        let end = after.find(|c| !is_word_char(c)).unwrap_or(after.len()) + offset;
+        if start == end {
+            return None;
+        }
+        Some(TextRange::new((start as u32).into(), (end as u32).into()))
+    }
+
+    /// Returns the number of chars before `offset`. The lines before the one
+    /// containing `offset` are counted from the index, without scanning them.
+    pub fn char_offset(&self, offset: TextSize, text: &str) -> usize {
+        let line = self.newlines.upper_bound(&offset) - 1;
+        let line_start = self.newlines[line];
+        let multibyte_len: usize = self
+            .utf16_lines
+            .iter()
+            .filter(|(&it, _)| (it as usize) < line)
+            .flat_map(|(_, chars)| chars)
+            .map(|c| usize::from(c.len()) - 1)
+            .sum();
+        usize::from(line_start) - multibyte_len
+            + text[TextRange::new(line_start, offset)].chars().count()
+    }
+
+    /// Returns the char starting at `offset`, or `None` if `offset` is not on a
+    /// char boundary or at the end of `text`.
+    pub fn char_at(&self, offset: TextSize, text: &str) -> Option<char> {
+        text.get(usize::from(offset)..)?.chars().next()
+    }
+
+    /// Returns the text between two UTF-16 columns of `line`, or `None` if the
+    /// columns are out of order or not within the line.
+    pub fn utf16_range_to_text<'a>(
+        &self,
+        line: u32,
+        start_col: u32,
+        end_col: u32,
+        text: &'a str,
+    ) -> Option<&'a str> {
+        let line_start = *self.newlines.get(line as usize)?;
+        let line_end = self.newlines.get(line as usize + 1).copied().unwrap_or(self.len);
+        let start = line_start + self.utf16_to_utf8_col(line, start_col);
+        let end = line_start + self.utf16_to_utf8_col(line, end_col);
+        if start > end || end > line_end {
+            return None;
+        }
+        text.get(usize::from(start)..usize::from(end))
+    }
+
     pub fn lines(&self, range: TextRange) -> impl Iterator<Item = TextRange> + '_ {
         let lo = self.newlines.lower_bound(&range.start());
         let hi = self.newlines.upper_bound(&range.end());
@@ -115,6 +307,143 @@ impl LineIndex {
             .filter(|it| !it.is_empty())
     }
 
+    /// Truncates `range` to at most `max_lines` lines, counting from the line
+    /// of its start.
+    pub fn truncate_to_lines(&self, range: TextRange, max_lines: u32) -> TextRange {
+        let start_line = self.newlines.upper_bound(&range.start()) - 1;
+        let end = match self.newlines.get(start_line + max_lines as usize) {
+            Some(&line_end) => range.end().min(line_end.max(range.start())),
+            None => range.end(),
+        };
+        TextRange::new(range.start(), end)
+    }
+
+    /// Returns the range of the given lines, including the newline of the last
+    /// one, or `None` if a line is past the end of the text.
+    pub fn byte_range(&self, lines: RangeInclusive<u32>) -> Option<TextRange> {
+        let (first, last) = (*lines.start() as usize, *lines.end() as usize);
+        if first > last {
+            return None;
+        }
+        let start = *self.newlines.get(first)?;
+        self.newlines.get(last)?;
+        let end = self.newlines.get(last + 1).copied().unwrap_or(self.len);
+        Some(TextRange::new(start, end))
+    }
+
+    /// Checks whether `range` starts and ends within the lines from
+    /// `first_line` to `last_line`, both inclusive.
+    pub fn range_within_lines(&self, range: TextRange, first_line: u32, last_line: u32) -> bool {
+        let start = self.line_col(range.start()).line;
+        let end = self.line_col(range.end()).line;
+        first_line <= start && end <= last_line
+    }
+
+    /// Checks whether `offset` is at the start of a line.
+    pub fn is_line_start(&self, offset: TextSize) -> bool {
+        self.newlines.binary_search(&offset).is_ok()
+    }
+
+    /// Expands `range` to cover the whole lines it touches, including the
+    /// newline of the last one.
+    pub fn expand_to_lines(&self, range: TextRange) -> TextRange {
+        let start_line = self.newlines.upper_bound(&range.start()) - 1;
+        let end_line = self.newlines.upper_bound(&range.end()) - 1;
+        let end = self.newlines.get(end_line + 1).copied().unwrap_or(self.len);
+        TextRange::new(self.newlines[start_line], end)
+    }
+
+    /// Returns the visual column of `offset`, where a tab advances to the
+    /// next of the sorted column `stops`. A tab past the last stop advances a
+    /// single column.
+    pub fn visual_col_with_stops(&self, offset: TextSize, stops: &[u32], text: &str) -> u32 {
+        let line = self.newlines.upper_bound(&offset) - 1;
+        let line_start = self.newlines[line];
+        text[TextRange::new(line_start, offset)].chars().fold(0, |col, c| match c {
+            '\t' => stops.iter().copied().find(|&stop| stop > col).unwrap_or(col + 1),
+            _ => col + 1,
+        })
+    }
+
+    /// Converts the byte column `col` of `line` into the column it is displayed
+    /// at, where a tab advances to the next multiple of `tab_width`.
+    pub fn logical_to_visual_col(&self, line: u32, col: u32, tab_width: u32, text: &str) -> u32 {
+        let tab_width = tab_width.max(1);
+        let start = self.newlines[line as usize];
+        text[TextRange::at(start, col.into())].chars().fold(0, |visual, c| match c {
+            '\t' => visual + tab_width - visual % tab_width,
+            _ => visual + 1,
+        })
+    }
+
+    /// Converts the displayed column `visual_col` of `line` back into a byte
+    /// column. A column inside a tab maps to the start of the tab, and one past
+    /// the end of the line to the end of the line.
+    pub fn visual_to_logical_col(
+        &self,
+        line: u32,
+        visual_col: u32,
+        tab_width: u32,
+        text: &str,
+    ) -> u32 {
+        let tab_width = tab_width.max(1);
+        let start = self.newlines[line as usize];
+        let end = self.newlines.get(line as usize + 1).copied().unwrap_or(self.len);
+        let line_text = text[TextRange::new(start, end)].trim_end_matches('\n');
+        let mut visual = 0;
+        for (idx, c) in line_text.char_indices() {
+            visual += match c {
+                '\t' => tab_width - visual % tab_width,
+                _ => 1,
+            };
+            if visual > visual_col {
+                return idx as u32;
+            }
+        }
+        line_text.len() as u32
+    }
+
+    /// Returns a copy of `text` with every tab replaced by the spaces reaching
+    /// the next multiple of `tab_width`, together with the index of the copy.
+    pub fn expand_tabs(&self, text: &str, tab_width: u32) -> (String, LineIndex) {
+        let tab_width = tab_width.max(1) as usize;
+        let ends = self.newlines.iter().skip(1).copied().chain(iter::once(TextSize::of(text)));
+        let mut res = String::with_capacity(text.len());
+        for (start, end) in self.newlines.iter().copied().zip(ends) {
+            let mut col = 0;
+            for c in text[TextRange::new(start, end)].chars() {
+                match c {
+                    '\t' => {
+                        let width = tab_width - col % tab_width;
+                        res.push_str(&" ".repeat(width));
+                        col += width;
+                    }
+                    _ => {
+                        res.push(c);
+                        col += 1;
+                    }
+                }
+            }
+        }
+        let line_index = LineIndex::new(&res);
+        (res, line_index)
+    }
+
+    /// Returns the numbers of lines which are empty or consist only of
+    /// whitespace. The empty line after a trailing newline is not counted.
+    pub fn blank_lines<'a>(&'a self, text: &'a str) -> impl Iterator<Item = u32> + 'a {
+        let ends = self.newlines.iter().skip(1).copied().chain(iter::once(TextSize::of(text)));
+        let line_count = self.newlines.len() - text.ends_with('\n') as usize;
+        let lines = self.newlines.iter().copied().zip(ends).take(line_count);
+        lines.enumerate().filter_map(move |(line, (lo, hi))| {
+            if text[TextRange::new(lo, hi)].trim().is_empty() {
+                Some(line as u32)
+            } else {
+                None
+            }
+        })
+    }
+
     fn utf8_to_utf16_col(&self, line: u32, col: TextSize) -> usize {
         let mut res: usize = col.into();
         if let Some(utf16_chars) = self.utf16_lines.get(&line) {
@@ -278,4 +607,260 @@ const C: char = \"メ メ\";
         let expected = vec![r(0, 1)];
         assert_eq!(actual, expected)
     }
+
+    #[test]
+    fn test_blank_lines() {
+        let text = "fn main() {\n\n    let x = 1;\n    \n}\n\t\nend";
+        let line_index = LineIndex::new(text);
+
+        let actual = line_index.blank_lines(text).collect::<Vec<_>>();
+        assert_eq!(actual, vec![1, 3, 5]);
+
+        let text = "fn main() {}\n\n";
+        let line_index = LineIndex::new(text);
+        assert_eq!(line_index.blank_lines(text).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_visual_col_with_stops() {
+        let stops = [4, 12, 20];
+        let text = "fn main() {}\n\tab\tc\td\n\t\t\t\tx";
+        let line_index = LineIndex::new(text);
+
+        let col = |offset: u32| line_index.visual_col_with_stops(offset.into(), &stops, text);
+        assert_eq!(col(13), 0);
+        assert_eq!(col(14), 4);
+        assert_eq!(col(17), 12);
+        assert_eq!(col(19), 20);
+        assert_eq!(col(25), 21);
+    }
+
+    #[test]
+    fn test_expand_to_lines() {
+        fn r(lo: u32, hi: u32) -> TextRange {
+            TextRange::new(lo.into(), hi.into())
+        }
+
+        let text = "fn foo() {}\nfn bar() {}\nfn baz() {}";
+        let line_index = LineIndex::new(text);
+
+        assert_eq!(line_index.expand_to_lines(r(5, 17)), r(0, 24));
+        assert_eq!(line_index.expand_to_lines(r(15, 15)), r(12, 24));
+        assert_eq!(line_index.expand_to_lines(r(28, 30)), r(24, 35));
+    }
+
+    #[test]
+    fn test_is_line_start() {
+        let index = LineIndex::new("hello\nworld");
+        assert!(index.is_line_start(0.into()));
+        assert!(!index.is_line_start(3.into()));
+        assert!(!index.is_line_start(5.into()));
+        assert!(index.is_line_start(6.into()));
+        assert!(!index.is_line_start(11.into()));
+
+        let index = LineIndex::new("hello\n");
+        assert!(index.is_line_start(6.into()));
+    }
+
+    #[test]
+    fn test_line_and_byte_col() {
+        let text = "hello\nмир world";
+        let index = LineIndex::new(text);
+        let offset = TextSize::from(text.find("world").unwrap() as u32);
+        assert_eq!(index.line_and_byte_col(offset), (1, 7));
+        assert_eq!(index.line_col(offset), LineCol { line: 1, col_utf16: 4 });
+        assert_eq!(index.line_and_byte_col(3.into()), (0, 3));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let bytes = b"hello\nw\xffrld\n!";
+        let index = LineIndex::from_bytes(bytes);
+        assert_eq!(index.newlines, vec![0.into(), 6.into(), 12.into()]);
+        assert_eq!(index.line_col(9.into()), LineCol { line: 1, col_utf16: 3 });
+        assert_eq!(
+            index.expand_to_lines(TextRange::empty(13.into())),
+            TextRange::new(12.into(), 13.into())
+        );
+
+        assert_eq!(
+            LineIndex::from_bytes("hello\nмир".as_bytes()).newlines,
+            LineIndex::new("hello\nмир").newlines
+        );
+    }
+
+    #[test]
+    fn test_from_parts() {
+        for text in ["", "hello", "hello\nмир world\n", "\n\n😀 a\nb ы\n"].iter() {
+            let expected = LineIndex::new(text);
+            assert_eq!(LineIndex::from_parts(expected.newlines.clone(), text), expected);
+        }
+    }
+
+    #[test]
+    fn test_delete_range() {
+        let text = "hello\nмир world\n\n😀 x ы\nlast";
+        let boundaries =
+            (0..=text.len()).filter(|&it| text.is_char_boundary(it)).collect::<Vec<_>>();
+        for &start in boundaries.iter() {
+            for &end in boundaries.iter().filter(|&&it| it >= start) {
+                let mut actual = LineIndex::new(text);
+                actual.delete_range(TextRange::new((start as u32).into(), (end as u32).into()));
+                let expected = LineIndex::new(&format!("{}{}", &text[..start], &text[end..]));
+                assert_eq!(actual, expected, "deleting {}..{}", start, end);
+            }
+        }
+    }
+
+    #[test]
+    fn test_offsets() {
+        let index = LineIndex::new("hello\nмир world\n");
+        let line_col = |line, col_utf16| LineCol { line, col_utf16 };
+        let positions =
+            [line_col(1, 4), line_col(0, 2), line_col(1, 0), line_col(0, 5), line_col(1, 9)];
+        let expected = positions.iter().map(|&it| Some(index.offset(it))).collect::<Vec<_>>();
+        assert_eq!(index.offsets(&positions), expected);
+        assert_eq!(index.offsets(&[line_col(3, 0)]), vec![None]);
+
+        // The results stay in input order, even though lines are grouped.
+        let positions = [line_col(1, 9), line_col(3, 0), line_col(0, 1), line_col(1, 9)];
+        assert_eq!(
+            index.offsets(&positions),
+            vec![Some(18.into()), None, Some(1.into()), Some(18.into())]
+        );
+    }
+
+    #[test]
+    fn test_truncate_to_lines() {
+        let text = (0..10).map(|it| format!("line {}\n", it)).collect::<String>();
+        let index = LineIndex::new(&text);
+        let range = TextRange::new(2.into(), TextSize::of(&*text));
+        assert_eq!(index.truncate_to_lines(range, 3), TextRange::new(2.into(), 21.into()));
+        assert_eq!(index.truncate_to_lines(range, 20), range);
+        assert_eq!(index.truncate_to_lines(range, 0), TextRange::empty(2.into()));
+
+        let short = TextRange::new(2.into(), 4.into());
+        assert_eq!(index.truncate_to_lines(short, 3), short);
+    }
+
+    #[test]
+    fn test_utf16_range_to_text() {
+        let text = "hello\nlet 😀 = мир;\n";
+        let index = LineIndex::new(text);
+        assert_eq!(index.utf16_range_to_text(1, 0, 3, text), Some("let"));
+        assert_eq!(index.utf16_range_to_text(1, 4, 6, text), Some("😀"));
+        assert_eq!(index.utf16_range_to_text(1, 9, 12, text), Some("мир"));
+        assert_eq!(index.utf16_range_to_text(1, 5, 6, text), None);
+        assert_eq!(index.utf16_range_to_text(0, 3, 2, text), None);
+        assert_eq!(index.utf16_range_to_text(0, 0, 7, text), None);
+        assert_eq!(index.utf16_range_to_text(5, 0, 0, text), None);
+    }
+
+    #[test]
+    fn test_char_at() {
+        let text = "fn мир() {}";
+        let index = LineIndex::new(text);
+        assert_eq!(index.char_at(0.into(), text), Some('f'));
+        assert_eq!(index.char_at(3.into(), text), Some('м'));
+        assert_eq!(index.char_at(4.into(), text), None);
+        assert_eq!(index.char_at(5.into(), text), Some('и'));
+        assert_eq!(index.char_at(13.into(), text), Some('}'));
+        assert_eq!(index.char_at(14.into(), text), None);
+    }
+
+    #[test]
+    fn test_line_end_col_utf16() {
+        let text = "fn main() {\n\n    let x = \"😀 мир\";\n}";
+        let index = LineIndex::new(text);
+        assert_eq!(index.line_end_col_utf16(0, text), Some(11));
+        assert_eq!(index.line_end_col_utf16(1, text), Some(0));
+        assert_eq!(index.line_end_col_utf16(2, text), Some(21));
+        assert_eq!(index.line_end_col_utf16(3, text), Some(1));
+        assert_eq!(index.line_end_col_utf16(4, text), None);
+    }
+
+    #[test]
+    fn test_word_range_at() {
+        let text = "fn main() {\n    let мир_1 = 92;\n}";
+        let index = LineIndex::new(text);
+        let word_at = |offset: usize| {
+            index.word_range_at((offset as u32).into(), text).map(|range| &text[range])
+        };
+        assert_eq!(word_at(0), Some("fn"));
+        assert_eq!(word_at(5), Some("main"));
+        assert_eq!(word_at(7), Some("main"));
+        assert_eq!(word_at(text.find("ир").unwrap()), Some("мир_1"));
+        assert_eq!(word_at(text.find(" = 92").unwrap()), Some("мир_1"));
+        assert_eq!(word_at(text.find(" {").unwrap() + 1), None);
+        assert_eq!(word_at(text.find("    let").unwrap() + 2), None);
+        assert_eq!(word_at(text.len()), None);
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let text = "fn main() {\n\t\tlet x\t= 92;\n\tмир\t}\n";
+        let index = LineIndex::new(text);
+        let (expanded, expanded_index) = index.expand_tabs(text, 4);
+        assert_eq!(expanded, "fn main() {\n        let x   = 92;\n    мир }\n");
+        assert_eq!(expanded_index, LineIndex::new(&expanded));
+    }
+
+    #[test]
+    fn test_range_within_lines() {
+        let text = "0\n1\n2\nthree\nfour\nfive\n6\n";
+        let index = LineIndex::new(text);
+        let start = text.find("three").unwrap() as u32;
+        let end = (text.find("five").unwrap() + 2) as u32;
+        let range = TextRange::new(start.into(), end.into());
+        assert!(index.range_within_lines(range, 3, 5));
+        assert!(index.range_within_lines(range, 0, 6));
+        assert!(!index.range_within_lines(range, 4, 6));
+        assert!(!index.range_within_lines(range, 0, 4));
+    }
+
+    #[test]
+    fn test_char_offset() {
+        let text = "let 😀 = 'м';\n\nfn main() {}\nмир 😀 92";
+        let index = LineIndex::new(text);
+        for (offset, _) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+            let expected = text[..offset].chars().count();
+            assert_eq!(index.char_offset((offset as u32).into(), text), expected);
+        }
+    }
+
+    #[test]
+    fn test_byte_range() {
+        let text = "fn main() {\n    92\n}";
+        let index = LineIndex::new(text);
+        assert_eq!(index.byte_range(1..=2).map(|it| &text[it]), Some("    92\n}"));
+        assert_eq!(index.byte_range(0..=0).map(|it| &text[it]), Some("fn main() {\n"));
+        assert_eq!(index.byte_range(1..=3), None);
+    }
+
+    #[test]
+    fn test_move_vertically() {
+        let text = "fn main() {\n    let x = 92;\n}\n";
+        let index = LineIndex::new(text);
+        let pos = |line, col_utf16| LineCol { line, col_utf16 };
+        assert_eq!(index.move_vertically(pos(1, 12), 1, text), pos(2, 1));
+        assert_eq!(index.move_vertically(pos(2, 1), -1, text), pos(1, 1));
+        assert_eq!(index.move_vertically(pos(1, 12), -1, text), pos(0, 11));
+        assert_eq!(index.move_vertically(pos(1, 12), 5, text), pos(3, 0));
+        assert_eq!(index.move_vertically(pos(1, 4), -10, text), pos(0, 4));
+    }
+
+    #[test]
+    fn test_visual_col() {
+        let text = "fn main() {\n\tlet x =\t92;\n}";
+        let index = LineIndex::new(text);
+        assert_eq!(index.logical_to_visual_col(1, 1, 4, text), 4);
+        assert_eq!(index.logical_to_visual_col(1, 8, 4, text), 11);
+        assert_eq!(index.logical_to_visual_col(1, 9, 4, text), 12);
+        assert_eq!(index.visual_to_logical_col(1, 2, 4, text), 0);
+        assert_eq!(index.visual_to_logical_col(1, 100, 4, text), 12);
+        for col in 0..=12 {
+            let visual = index.logical_to_visual_col(1, col, 4, text);
+            assert_eq!(index.visual_to_logical_col(1, visual, 4, text), col);
+        }
+    }
 }
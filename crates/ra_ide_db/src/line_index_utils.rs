@@ -0,0 +1,627 @@
+//! Functionality which needs both a `LineIndex` and a `TextEdit`.
+use std::fmt::Write;
+
+use ra_syntax::{TextRange, TextSize};
+use ra_text_edit::{TextEdit, TextEditBuilder};
+
+use crate::line_index::{LineCol, LineIndex};
+
+/// Number of unchanged lines shown around each change in a unified diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Renders the result of applying `edit` to `old_text` as a unified diff.
+pub fn to_unified_diff(edit: &TextEdit, old_text: &str, file_name: &str) -> String {
+    let line_index = LineIndex::new(old_text);
+    let mut new_text = old_text.to_string();
+    edit.apply(&mut new_text);
+
+    let old_lines = split_lines(old_text);
+    let changes = line_changes(edit, &line_index, old_text, &new_text);
+
+    let mut buf = format!("--- a/{}\n+++ b/{}\n", file_name, file_name);
+    let mut rest = &changes[..];
+    while let Some(first) = rest.first() {
+        let len = 1 + rest
+            .windows(2)
+            .take_while(|w| w[1].old_start - w[0].old_end() <= 2 * DIFF_CONTEXT)
+            .count();
+        let (hunk, tail) = rest.split_at(len);
+        rest = tail;
+
+        let last = hunk.last().unwrap();
+        let old_start = first.old_start.saturating_sub(DIFF_CONTEXT);
+        let old_end = (last.old_end() + DIFF_CONTEXT).min(old_lines.len());
+        let new_start = first.new_start - (first.old_start - old_start);
+        let new_len = hunk.iter().fold(old_end - old_start, |len, change| {
+            len + change.new_lines.len() - change.old_lines.len()
+        });
+        writeln!(
+            buf,
+            "@@ -{} +{} @@",
+            hunk_span(old_start, old_end - old_start),
+            hunk_span(new_start, new_len)
+        )
+        .unwrap();
+
+        let mut line = old_start;
+        for change in hunk {
+            old_lines[line..change.old_start].iter().for_each(|it| push_line(&mut buf, ' ', it));
+            change.old_lines.iter().for_each(|it| push_line(&mut buf, '-', it));
+            change.new_lines.iter().for_each(|it| push_line(&mut buf, '+', it));
+            line = change.old_end();
+        }
+        old_lines[line..old_end].iter().for_each(|it| push_line(&mut buf, ' ', it));
+    }
+    buf
+}
+
+/// Numbers of lines added and removed by an edit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineStats {
+    pub added: u32,
+    pub removed: u32,
+}
+
+/// Counts the lines `edit` adds to and removes from `old_text`, like the `+`
+/// and `-` lines of `to_unified_diff`: a changed line counts as both.
+pub fn line_stats(edit: &TextEdit, old_text: &str) -> LineStats {
+    let line_index = LineIndex::new(old_text);
+    let mut new_text = old_text.to_string();
+    edit.apply(&mut new_text);
+
+    let changes = line_changes(edit, &line_index, old_text, &new_text);
+    changes.iter().fold(LineStats::default(), |stats, change| LineStats {
+        added: stats.added + change.new_lines.len() as u32,
+        removed: stats.removed + change.old_lines.len() as u32,
+    })
+}
+
+/// An indel with its deletion given as lines and columns, like an LSP
+/// `TextEdit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineColEdit {
+    pub range: (LineCol, LineCol),
+    pub new_text: String,
+}
+
+/// Maps each indel of `edit` to the start and end of its deletion, together
+/// with its insertion.
+pub fn to_line_col_ranges(
+    edit: &TextEdit,
+    line_index: &LineIndex,
+) -> Vec<(LineCol, LineCol, String)> {
+    edit.iter()
+        .map(|indel| {
+            let start = line_index.line_col(indel.delete.start());
+            let end = line_index.line_col(indel.delete.end());
+            (start, end, indel.insert.clone())
+        })
+        .collect()
+}
+
+/// Like `to_line_col_ranges`, but returns `LineColEdit`s. The columns count
+/// UTF-16 code units, as LSP positions do by default.
+pub fn to_line_col_edits(edit: &TextEdit, line_index: &LineIndex) -> Vec<LineColEdit> {
+    to_line_col_ranges(edit, line_index)
+        .into_iter()
+        .map(|(start, end, new_text)| LineColEdit { range: (start, end), new_text })
+        .collect()
+}
+
+/// Applies `edit` to text stored as `lines` without their newlines, splitting
+/// and joining lines where the edit inserts or deletes newlines. `line_index`
+/// must be the index of the text before the edit.
+pub fn apply_to_lines(edit: &TextEdit, lines: &mut Vec<String>, line_index: &LineIndex) {
+    for indel in edit.iter().rev() {
+        let (start_line, start_col) = line_index.line_and_byte_col(indel.delete.start());
+        let (end_line, end_col) = line_index.line_and_byte_col(indel.delete.end());
+        let (start_line, end_line) = (start_line as usize, end_line as usize);
+        let text = format!(
+            "{}{}{}",
+            &lines[start_line][..start_col as usize],
+            indel.insert,
+            &lines[end_line][end_col as usize..]
+        );
+        lines.splice(start_line..=end_line, text.split('\n').map(String::from));
+    }
+}
+
+/// Builds an edit of `text` from changes to whole lines: `Some` replaces the
+/// contents of a line, keeping its newline, and `None` deletes the line. Every
+/// line may be changed at most once, and lines past the end are ignored.
+pub fn from_line_edits(
+    line_index: &LineIndex,
+    text: &str,
+    edits: &[(u32, Option<String>)],
+) -> TextEdit {
+    let mut builder = TextEditBuilder::default();
+    for (line, new_line) in edits {
+        let range = match line_index.byte_range(*line..=*line) {
+            Some(it) => it,
+            None => continue,
+        };
+        match new_line {
+            Some(new_line) => {
+                let len = TextSize::of(text[range].trim_end_matches('\n'));
+                builder.replace(TextRange::at(range.start(), len), new_line.clone());
+            }
+            None => builder.delete(range),
+        }
+    }
+    builder.finish()
+}
+
+/// Returns a function mapping positions in the text before `edit` to positions
+/// in the text after it. Positions inside a deleted range move to the end of
+/// its replacement, and positions at an insertion point move past the inserted
+/// text, like a cursor typing it would.
+pub fn remap_positions<'a>(
+    old_index: &'a LineIndex,
+    new_index: &'a LineIndex,
+    edit: &'a TextEdit,
+) -> impl Fn(LineCol) -> LineCol + 'a {
+    move |line_col| {
+        let offset = old_index.offset(line_col);
+        let indel = edit.iter().find(|it| {
+            let is_inside = it.delete.start() < offset && offset < it.delete.end();
+            let is_insertion_point = it.delete.is_empty() && it.delete.start() == offset;
+            is_inside || is_insertion_point
+        });
+        let new_offset = match indel {
+            Some(indel) => {
+                edit.apply_to_offset(indel.delete.start()).unwrap() + TextSize::of(&indel.insert)
+            }
+            None => edit.apply_to_offset(offset).unwrap(),
+        };
+        new_index.line_col(new_offset)
+    }
+}
+
+/// The lines around a single indel, before and after applying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangePreview {
+    /// Start of the change in the old text
+    pub line_col: LineCol,
+    pub old: String,
+    pub new: String,
+}
+
+/// Previews every indel of `edit` on its own, showing up to `context_lines`
+/// lines before and after the lines it changes.
+pub fn preview(
+    edit: &TextEdit,
+    text: &str,
+    line_index: &LineIndex,
+    context_lines: u32,
+) -> Vec<ChangePreview> {
+    let last_line = line_index.newlines.len() as u32 - 1;
+    edit.iter()
+        .map(|indel| {
+            let line_col = line_index.line_col(indel.delete.start());
+            let first = line_col.line.saturating_sub(context_lines);
+            let last =
+                (line_index.line_col(indel.delete.end()).line + context_lines).min(last_line);
+            let start = usize::from(line_index.newlines[first as usize]);
+            let end = line_index
+                .newlines
+                .get(last as usize + 1)
+                .map_or(text.len(), |&it| usize::from(it) - 1);
+
+            let old = text[start..end].to_string();
+            let new = format!(
+                "{}{}{}",
+                &text[start..indel.delete.start().into()],
+                indel.insert,
+                &text[indel.delete.end().into()..end]
+            );
+            ChangePreview { line_col, old, new }
+        })
+        .collect()
+}
+
+/// Applies `edit` to `text`, indenting the continuation lines of each
+/// multi-line insertion to the column where the insertion starts.
+///
+/// The indentation copies the leading whitespace of the line as is, so mixed
+/// tabs and spaces are kept, and pads it with spaces up to the column.
+/// Columns count a tab as advancing to the next multiple of `tab_width`.
+/// Empty continuation lines stay empty, unless the text after the insertion
+/// continues them.
+pub fn apply_reindented(
+    edit: &TextEdit,
+    text: String,
+    line_index: &LineIndex,
+    tab_width: u32,
+) -> String {
+    let mut builder = TextEditBuilder::default();
+    for indel in edit.iter() {
+        let insert = if indel.insert.contains('\n') {
+            let indent = indent_at(line_index, &text, indel.delete.start(), tab_width);
+            let lines = indel.insert.split('\n').collect::<Vec<_>>();
+            let last = lines.len() - 1;
+            lines
+                .iter()
+                .enumerate()
+                .map(|(idx, line)| match idx {
+                    0 => line.to_string(),
+                    // The last line continues with the text after the insertion.
+                    _ if line.is_empty() && idx != last => String::new(),
+                    _ => format!("{}{}", indent, line),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            indel.insert.clone()
+        };
+        builder.replace(indel.delete, insert);
+    }
+    let mut text = text;
+    builder.finish().apply(&mut text);
+    text
+}
+
+/// Returns the whitespace reaching the column of `offset`.
+fn indent_at(line_index: &LineIndex, text: &str, offset: TextSize, tab_width: u32) -> String {
+    let (line, _) = line_index.line_and_byte_col(offset);
+    let line_start: usize = line_index.newlines[line as usize].into();
+    let prefix = &text[line_start..offset.into()];
+
+    let tab_width = tab_width.max(1);
+    let col = |text: &str| {
+        text.chars().fold(0, |col, c| match c {
+            '\t' => col + tab_width - col % tab_width,
+            _ => col + 1,
+        })
+    };
+    let leading = &prefix[..prefix.len() - prefix.trim_start_matches(&[' ', '\t'][..]).len()];
+    let padding = col(prefix) - col(leading);
+    format!("{}{}", leading, " ".repeat(padding as usize))
+}
+
+/// A run of lines replaced by an edit, with lines that stayed the same trimmed
+/// off both ends.
+struct LineChange<'a> {
+    old_start: usize,
+    old_lines: Vec<&'a str>,
+    new_start: usize,
+    new_lines: Vec<&'a str>,
+}
+
+impl LineChange<'_> {
+    fn old_end(&self) -> usize {
+        self.old_start + self.old_lines.len()
+    }
+}
+
+fn line_changes<'a>(
+    edit: &TextEdit,
+    line_index: &LineIndex,
+    old_text: &'a str,
+    new_text: &'a str,
+) -> Vec<LineChange<'a>> {
+    let line_start = |line: usize| -> usize {
+        line_index.newlines.get(line).map_or(old_text.len(), |&it| it.into())
+    };
+
+    let mut res = Vec::new();
+    // Byte and line delta between the old and the new text, up to the current change.
+    let mut delta = 0i64;
+    let mut line_delta = 0i64;
+    // The last line has no newline to end it, so every indel reaching it
+    // shares that line.
+    let is_unterminated_eof = |offset: usize| offset == old_text.len() && !old_text.ends_with('\n');
+    let mut indels = edit.iter().peekable();
+    while let Some(indel) = indels.next() {
+        // Expand the change to whole lines, merging changes which share lines.
+        let first_line = line_index.line_col(indel.delete.start()).line as usize;
+        let start = line_start(first_line);
+        let mut end = line_end(line_index, indel.delete.start(), indel.delete.end(), old_text);
+        let mut change_delta = indel_delta(indel);
+        while let Some(next) = indels.peek() {
+            if usize::from(next.delete.start()) >= end && !is_unterminated_eof(end) {
+                break;
+            }
+            end = end.max(line_end(line_index, next.delete.start(), next.delete.end(), old_text));
+            change_delta += indel_delta(next);
+            indels.next();
+        }
+
+        let new_start = (start as i64 + delta) as usize;
+        let new_end = (end as i64 + delta + change_delta) as usize;
+        let mut old_lines = split_lines(&old_text[start..end]);
+        let mut new_lines = split_lines(&new_text[new_start..new_end]);
+        delta += change_delta;
+
+        let mut old_start = first_line;
+        let mut new_start = (first_line as i64 + line_delta) as usize;
+        line_delta += new_lines.len() as i64 - old_lines.len() as i64;
+
+        let prefix = old_lines.iter().zip(new_lines.iter()).take_while(|(l, r)| l == r).count();
+        old_lines.drain(..prefix);
+        new_lines.drain(..prefix);
+        old_start += prefix;
+        new_start += prefix;
+        let suffix =
+            old_lines.iter().rev().zip(new_lines.iter().rev()).take_while(|(l, r)| l == r).count();
+        old_lines.truncate(old_lines.len() - suffix);
+        new_lines.truncate(new_lines.len() - suffix);
+
+        if !old_lines.is_empty() || !new_lines.is_empty() {
+            res.push(LineChange { old_start, old_lines, new_start, new_lines });
+        }
+    }
+    res
+}
+
+/// Returns the end of the last line touched by the `start..end` range,
+/// including its newline.
+fn line_end(line_index: &LineIndex, start: TextSize, end: TextSize, text: &str) -> usize {
+    let line = line_index.line_col(end).line as usize;
+    let line_start = line_index.newlines[line];
+    if end == line_start && start < end {
+        return end.into();
+    }
+    line_index.newlines.get(line + 1).map_or(text.len(), |&it| it.into())
+}
+
+fn indel_delta(indel: &ra_text_edit::Indel) -> i64 {
+    indel.insert.len() as i64 - u32::from(indel.delete.len()) as i64
+}
+
+/// Splits `text` into lines, keeping the line terminators.
+fn split_lines(text: &str) -> Vec<&str> {
+    let mut res = Vec::new();
+    let mut start = 0;
+    for (idx, _) in text.match_indices('\n') {
+        res.push(&text[start..=idx]);
+        start = idx + 1;
+    }
+    if start < text.len() {
+        res.push(&text[start..]);
+    }
+    res
+}
+
+fn hunk_span(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+fn push_line(buf: &mut String, marker: char, line: &str) {
+    buf.push(marker);
+    buf.push_str(line);
+    if !line.ends_with('\n') {
+        buf.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let text = "\
+fn main() {
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    let d = 4;
+    let e = 5;
+    let f = 6;
+}
+";
+        let offset = TextSize::from(text.find("d = 4").unwrap() as u32);
+        let edit = TextEdit::replace(TextRange::at(offset, 1.into()), "x".to_string());
+
+        let actual = to_unified_diff(&edit, text, "src/main.rs");
+        let expected = "\
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -2,7 +2,7 @@
+     let a = 1;
+     let b = 2;
+     let c = 3;
+-    let d = 4;
++    let x = 4;
+     let e = 5;
+     let f = 6;
+ }
+";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unified_diff_at_eof_without_newline() {
+        let text = "fn main() {}";
+        let mut builder = TextEditBuilder::default();
+        builder.replace(TextRange::new(11.into(), 12.into()), "92 }".to_string());
+        builder.insert(12.into(), "\n".to_string());
+        let edit = builder.finish();
+
+        let actual = to_unified_diff(&edit, text, "src/main.rs");
+        let expected = "\
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,1 +1,1 @@
+-fn main() {}
+\\ No newline at end of file
++fn main() {92 }
+";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_line_stats() {
+        let text = "fn main() {\n    foo();\n    bar();\n}\n";
+        let start = TextSize::from(text.find("    foo").unwrap() as u32);
+        let end = TextSize::from(text.find('}').unwrap() as u32);
+        let edit = TextEdit::replace(TextRange::new(start, end), "    baz();\n".to_string());
+        assert_eq!(line_stats(&edit, text), LineStats { added: 1, removed: 2 });
+
+        let edit = TextEdit::insert(0.into(), "// hello\n".to_string());
+        assert_eq!(line_stats(&edit, text), LineStats { added: 1, removed: 0 });
+        assert_eq!(line_stats(&TextEdit::default(), text), LineStats::default());
+
+        let text = "fn main() {}";
+        let mut builder = TextEditBuilder::default();
+        builder.replace(TextRange::new(11.into(), 12.into()), "92 }".to_string());
+        builder.insert(12.into(), "\n".to_string());
+        assert_eq!(line_stats(&builder.finish(), text), LineStats { added: 1, removed: 1 });
+    }
+
+    #[test]
+    fn test_to_line_col_ranges() {
+        let text = "fn main() {\n    foo();\n}\n";
+        let line_index = LineIndex::new(text);
+        let mut builder = ra_text_edit::TextEditBuilder::default();
+        builder.replace(TextRange::new(3.into(), 7.into()), "start".to_string());
+        builder.delete(TextRange::new(10.into(), 22.into()));
+        let edit = builder.finish();
+
+        let line_col = |line, col_utf16| LineCol { line, col_utf16 };
+        assert_eq!(
+            to_line_col_ranges(&edit, &line_index),
+            vec![
+                (line_col(0, 3), line_col(0, 7), "start".to_string()),
+                (line_col(0, 10), line_col(1, 10), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_line_col_edits() {
+        let text = "fn main() {\n    foo();\n}\n";
+        let line_index = LineIndex::new(text);
+        let mut builder = ra_text_edit::TextEditBuilder::default();
+        builder.replace(TextRange::new(3.into(), 7.into()), "start".to_string());
+        builder.delete(TextRange::new(10.into(), 22.into()));
+        let edit = builder.finish();
+
+        let line_col = |line, col_utf16| LineCol { line, col_utf16 };
+        let line_col_edit = |start, end, new_text: &str| LineColEdit {
+            range: (start, end),
+            new_text: new_text.to_string(),
+        };
+        assert_eq!(
+            to_line_col_edits(&edit, &line_index),
+            vec![
+                line_col_edit(line_col(0, 3), line_col(0, 7), "start"),
+                line_col_edit(line_col(0, 10), line_col(1, 10), ""),
+            ]
+        );
+
+        // `😀` is 4 bytes in UTF-8 but 2 code units in UTF-16.
+        let text = "let s = \"😀\";\n";
+        let start = TextSize::from(text.find('😀').unwrap() as u32);
+        let edit = TextEdit::replace(TextRange::at(start, TextSize::of('😀')), "x".to_string());
+        assert_eq!(
+            to_line_col_edits(&edit, &LineIndex::new(text)),
+            vec![line_col_edit(line_col(0, 9), line_col(0, 11), "x")]
+        );
+    }
+
+    #[test]
+    fn test_apply_reindented() {
+        let text = "fn main() {\n\t  foo();\n}\n";
+        let line_index = LineIndex::new(text);
+        let offset = TextSize::from(text.find("foo").unwrap() as u32);
+        let edit = TextEdit::insert(offset, "bar();\n\nbaz();\n".to_string());
+
+        let actual = apply_reindented(&edit, text.to_string(), &line_index, 4);
+        assert_eq!(actual, "fn main() {\n\t  bar();\n\n\t  baz();\n\t  foo();\n}\n");
+
+        let offset = TextSize::from(text.find("();").unwrap() as u32);
+        let edit = TextEdit::insert(offset, "::<\n>".to_string());
+        let actual = apply_reindented(&edit, text.to_string(), &line_index, 4);
+        assert_eq!(actual, "fn main() {\n\t  foo::<\n\t     >();\n}\n");
+    }
+
+    #[test]
+    fn test_remap_positions() {
+        let old_text = "fn main() {\n    foo();\n    bar();\n}\n";
+        let offset = TextSize::from(old_text.find("    bar").unwrap() as u32);
+        let edit = TextEdit::insert(offset, "    baz();\n".to_string());
+        let mut new_text = old_text.to_string();
+        edit.apply(&mut new_text);
+        let old_index = LineIndex::new(old_text);
+        let new_index = LineIndex::new(&new_text);
+
+        let remap = remap_positions(&old_index, &new_index, &edit);
+        let line_col = |line, col_utf16| LineCol { line, col_utf16 };
+        assert_eq!(remap(line_col(1, 4)), line_col(1, 4));
+        assert_eq!(remap(line_col(2, 0)), line_col(3, 0));
+        assert_eq!(remap(line_col(2, 4)), line_col(3, 4));
+        assert_eq!(remap(line_col(3, 0)), line_col(4, 0));
+
+        let start = TextSize::from(old_text.find("foo").unwrap() as u32);
+        let edit = TextEdit::replace(TextRange::at(start, 5.into()), "x".to_string());
+        let mut new_text = old_text.to_string();
+        edit.apply(&mut new_text);
+        let new_index = LineIndex::new(&new_text);
+        let remap = remap_positions(&old_index, &new_index, &edit);
+        assert_eq!(remap(line_col(1, 6)), line_col(1, 5));
+        assert_eq!(remap(line_col(1, 9)), line_col(1, 5));
+    }
+
+    #[test]
+    fn test_preview() {
+        let text = "fn main() {\n    let a = 1;\n    let b = 2;\n    let c = 3;\n}\n";
+        let line_index = LineIndex::new(text);
+        let mut builder = TextEditBuilder::default();
+        let offset = text.find("b = 2").unwrap() as u32;
+        builder.replace(TextRange::at(offset.into(), 1.into()), "x".to_string());
+        builder.insert(0.into(), "// main\n".to_string());
+        let edit = builder.finish();
+
+        let previews = preview(&edit, text, &line_index, 1);
+        assert_eq!(
+            previews,
+            vec![
+                ChangePreview {
+                    line_col: LineCol { line: 0, col_utf16: 0 },
+                    old: "fn main() {\n    let a = 1;".to_string(),
+                    new: "// main\nfn main() {\n    let a = 1;".to_string(),
+                },
+                ChangePreview {
+                    line_col: LineCol { line: 2, col_utf16: 8 },
+                    old: "    let a = 1;\n    let b = 2;\n    let c = 3;".to_string(),
+                    new: "    let a = 1;\n    let x = 2;\n    let c = 3;".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_to_lines() {
+        let text = "fn main() {\n    foo();\n    bar();\n}";
+        let line_index = LineIndex::new(text);
+        let mut builder = TextEditBuilder::default();
+        let start = text.find("foo").unwrap() as u32;
+        let end = text.find("bar").unwrap() as u32;
+        builder.delete(TextRange::new(start.into(), end.into()));
+        let offset = text.find('}').unwrap() as u32;
+        builder.insert(offset.into(), "    baz();\n    quux();\n".to_string());
+        let edit = builder.finish();
+
+        let mut lines = text.split('\n').map(String::from).collect::<Vec<_>>();
+        apply_to_lines(&edit, &mut lines, &line_index);
+        let mut expected = text.to_string();
+        edit.apply(&mut expected);
+        assert_eq!(lines.join("\n"), expected);
+        assert_eq!(lines, ["fn main() {", "    bar();", "    baz();", "    quux();", "}"]);
+    }
+
+    #[test]
+    fn test_from_line_edits() {
+        let text = "fn main() {\n    foo();\n    bar();\n}\n";
+        let line_index = LineIndex::new(text);
+        let edits = [(1, Some("    baz();".to_string())), (2, None), (7, None)];
+        let edit = from_line_edits(&line_index, text, &edits);
+        let mut actual = text.to_string();
+        edit.apply(&mut actual);
+        assert_eq!(actual, "fn main() {\n    baz();\n}\n");
+    }
+}
@@ -15,7 +15,7 @@ pub(super) enum StmtWithSemi {
 const EXPR_FIRST: TokenSet = LHS_FIRST;
 
 pub(super) fn expr(p: &mut Parser) -> (Option<CompletedMarker>, BlockLike) {
-    let r = Restrictions { forbid_structs: false, prefer_stmt: false };
+    let r = Restrictions { forbid_structs: false, prefer_stmt: false, allow_let: false };
     expr_bp(p, r, 1)
 }
 
@@ -40,15 +40,22 @@ pub(super) fn expr_with_attrs(p: &mut Parser) -> bool {
 }
 
 pub(super) fn expr_stmt(p: &mut Parser) -> (Option<CompletedMarker>, BlockLike) {
-    let r = Restrictions { forbid_structs: false, prefer_stmt: true };
+    let r = Restrictions { forbid_structs: false, prefer_stmt: true, allow_let: false };
     expr_bp(p, r, 1)
 }
 
 fn expr_no_struct(p: &mut Parser) {
-    let r = Restrictions { forbid_structs: true, prefer_stmt: false };
+    let r = Restrictions { forbid_structs: true, prefer_stmt: false, allow_let: false };
     expr_bp(p, r, 1);
 }
 
+/// Parses the expression of an `if` or `while` condition, where `let`
+/// bindings may be chained with `&&`.
+fn cond_expr(p: &mut Parser) -> Option<CompletedMarker> {
+    let r = Restrictions { forbid_structs: true, prefer_stmt: false, allow_let: true };
+    expr_bp(p, r, 1).0
+}
+
 fn is_expr_stmt_attr_allowed(kind: SyntaxKind) -> bool {
     match kind {
         BIN_EXPR | RANGE_EXPR | IF_EXPR => false,
@@ -208,6 +215,7 @@ pub(super) fn expr_block_contents(p: &mut Parser) {
 struct Restrictions {
     forbid_structs: bool,
     prefer_stmt: bool,
+    allow_let: bool,
 }
 
 /// Binding powers of operators for a Pratt parser.
@@ -293,7 +301,7 @@ fn expr_bp(p: &mut Parser, mut r: Restrictions, bp: u8) -> (Option<CompletedMark
         // fn foo() {
         //     v = {1}&2;
         // }
-        r = Restrictions { prefer_stmt: false, ..r };
+        r = Restrictions { prefer_stmt: false, allow_let: r.allow_let && op == T![&&], ..r };
 
         if is_range {
             // test postfix_range
@@ -345,6 +353,12 @@ fn lhs(p: &mut Parser, r: Restrictions) -> Option<(CompletedMarker, BlockLike)>
             p.bump_any();
             PREFIX_EXPR
         }
+        // test let_chain
+        // fn foo() {
+        //     if let Some(a) = x && let Some(b) = y {}
+        //     while x && let Some(b) = y && z {}
+        // }
+        T![let] if r.allow_let => return Some((let_expr(p, r), BlockLike::NotBlock)),
         _ => {
             // test full_range_expr
             // fn foo() { xs[..]; }
@@ -370,10 +384,22 @@ fn lhs(p: &mut Parser, r: Restrictions) -> Option<(CompletedMarker, BlockLike)>
         }
     };
     // parse the interior of the unary expression
-    expr_bp(p, r, 255);
+    expr_bp(p, Restrictions { allow_let: false, ..r }, 255);
     Some((m.complete(p, kind), BlockLike::NotBlock))
 }
 
+// The scrutinee binds tighter than `&&` and `||`, so those always continue
+// the chain rather than the scrutinee.
+fn let_expr(p: &mut Parser, r: Restrictions) -> CompletedMarker {
+    assert!(p.at(T![let]));
+    let m = p.start();
+    p.bump(T![let]);
+    patterns::pattern_top(p);
+    p.expect(T![=]);
+    expr_bp(p, Restrictions { allow_let: false, ..r }, 5);
+    m.complete(p, LET_EXPR)
+}
+
 fn postfix_expr(
     p: &mut Parser,
     mut lhs: CompletedMarker,
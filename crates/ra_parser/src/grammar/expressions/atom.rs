@@ -344,11 +344,11 @@ fn for_expr(p: &mut Parser, m: Option<Marker>) -> CompletedMarker {
 // }
 fn cond(p: &mut Parser) {
     let m = p.start();
-    if p.eat(T![let]) {
-        patterns::pattern_top(p);
-        p.expect(T![=]);
+    match cond_expr(p) {
+        // a lone `let` keeps the flat `let PAT = EXPR` shape of the condition
+        Some(cm) if cm.kind() == LET_EXPR => cm.undo_completion(p).abandon(p),
+        _ => (),
     }
-    expr_no_struct(p);
     m.complete(p, CONDITION);
 }
 
@@ -592,7 +592,7 @@ fn meta_var_expr(p: &mut Parser) -> CompletedMarker {
     let m = p.start();
     p.bump(L_DOLLAR);
     let (completed, _is_block) =
-        expr_bp(p, Restrictions { forbid_structs: false, prefer_stmt: false }, 1);
+        expr_bp(p, Restrictions { forbid_structs: false, prefer_stmt: false, allow_let: false }, 1);
 
     match (completed, p.current()) {
         (Some(it), R_DOLLAR) => {
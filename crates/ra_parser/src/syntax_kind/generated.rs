@@ -193,6 +193,7 @@ pub enum SyntaxKind {
     RECORD_FIELD,
     EFFECT_EXPR,
     BOX_EXPR,
+    LET_EXPR,
     CALL_EXPR,
     INDEX_EXPR,
     METHOD_CALL_EXPR,
@@ -325,3 +325,33 @@ where
     assert_eq!("for<'a> F", pred.type_ref().unwrap().syntax().text().to_string());
     assert_bound("Fn(&'a str)", bounds.next());
 }
+
+#[test]
+fn test_comment_as_doc_attr() {
+    let file = SourceFile::parse(
+        r#"
+//! Inner docs
+/// Says "hi"
+// not docs
+/** Block */
+fn foo() {}
+"#,
+    )
+    .ok()
+    .unwrap();
+    let attrs = file
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token().and_then(Comment::cast))
+        .map(|it| it.as_doc_attr())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attrs,
+        vec![
+            Some(r#"#![doc = " Inner docs"]"#.to_string()),
+            Some(r#"#[doc = " Says \"hi\""]"#.to_string()),
+            None,
+            Some(r#"#[doc = " Block "]"#.to_string()),
+        ]
+    );
+}
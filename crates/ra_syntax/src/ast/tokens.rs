@@ -21,6 +21,21 @@ impl Comment {
         }
         unreachable!()
     }
+
+    /// Returns the `#[doc = "..."]` attribute equivalent to this doc comment,
+    /// or `#![doc = "..."]` for an inner one.
+    pub fn as_doc_attr(&self) -> Option<std::string::String> {
+        let bang = match self.kind().doc? {
+            CommentPlacement::Inner => "!",
+            CommentPlacement::Outer => "",
+        };
+        let text = self.text().as_str();
+        let mut content = &text[self.prefix().len()..];
+        if self.kind().shape.is_block() && content.ends_with("*/") {
+            content = &content[..content.len() - 2];
+        }
+        Some(format!("#{}[doc = {:?}]", bang, content))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
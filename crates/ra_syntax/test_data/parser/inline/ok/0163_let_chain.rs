@@ -0,0 +1,4 @@
+fn foo() {
+    if let Some(a) = x && let Some(b) = y {}
+    while x && let Some(b) = y && z {}
+}
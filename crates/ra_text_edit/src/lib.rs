@@ -3,25 +3,92 @@
 //! `rust-analyzer` never mutates text itself and only sends diffs to clients,
 //! so `TextEdit` is the ultimate representation of the work done by
 //! rust-analyzer.
-use std::{slice, vec};
+use std::{fmt, iter, mem, slice, vec};
 
 pub use text_size::{TextRange, TextSize};
 
 /// `InsertDelete` -- a single "atomic" change to text
 ///
 /// Must not overlap with other `InDel`s
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_crate::Serialize, serde_crate::Deserialize),
+    serde(crate = "serde_crate")
+)]
 pub struct Indel {
     pub insert: String,
     /// Refers to offsets in the original text
     pub delete: TextRange,
 }
 
+/// With the `serde` feature, serializes as the list of its indels.
 #[derive(Default, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_crate::Serialize),
+    serde(crate = "serde_crate", transparent)
+)]
 pub struct TextEdit {
     indels: Vec<Indel>,
 }
 
+/// Checks the indels like `TextEditBuilder::finish`, except that they must
+/// already be sorted.
+#[cfg(feature = "serde")]
+impl<'de> serde_crate::Deserialize<'de> for TextEdit {
+    fn deserialize<D>(deserializer: D) -> Result<TextEdit, D::Error>
+    where
+        D: serde_crate::Deserializer<'de>,
+    {
+        use serde_crate::de::{Deserialize, Error};
+
+        let indels = Vec::<Indel>::deserialize(deserializer)?;
+        let is_disjoint = indels
+            .iter()
+            .zip(indels.iter().skip(1))
+            .all(|(l, r)| l.delete.end() <= r.delete.start());
+        if !is_disjoint {
+            return Err(D::Error::custom("indels are not sorted or overlap"));
+        }
+        Ok(TextEdit { indels })
+    }
+}
+
+/// Error returned when an indel does not fit the text it is applied to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyError {
+    /// Index of the failing indel in the edit, `0` for a single indel
+    pub index: usize,
+    /// Offset which is past the end of the text or not on a char boundary
+    pub offset: TextSize,
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "indel {} has an invalid offset {:?}", self.index, self.offset)
+    }
+}
+
+impl std::error::Error for ApplyError {}
+
+/// Error returned when building an edit out of overlapping indels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlapError {
+    /// Deleted range of the first of the overlapping indels
+    pub first: TextRange,
+    /// Deleted range of the second of the overlapping indels
+    pub second: TextRange,
+}
+
+impl fmt::Display for OverlapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "indels at {:?} and {:?} overlap", self.first, self.second)
+    }
+}
+
+impl std::error::Error for OverlapError {}
+
 #[derive(Debug, Default, Clone)]
 pub struct TextEditBuilder {
     indels: Vec<Indel>,
@@ -38,11 +105,93 @@ impl Indel {
         Indel { delete: range, insert: replace_with }
     }
 
+    /// Checks whether this indel only inserts text. An indel which neither
+    /// inserts nor deletes anything is both an insertion and a deletion.
+    pub fn is_insert(&self) -> bool {
+        self.delete.is_empty()
+    }
+
+    /// Checks whether this indel only deletes text.
+    pub fn is_delete(&self) -> bool {
+        self.insert.is_empty()
+    }
+
+    /// Checks whether this indel both deletes and inserts text.
+    pub fn is_replace(&self) -> bool {
+        !self.is_insert() && !self.is_delete()
+    }
+
+    /// Returns a copy of this indel which deletes `range` instead.
+    pub fn with_delete(&self, range: TextRange) -> Indel {
+        Indel::replace(range, self.insert.clone())
+    }
+
+    /// Returns a copy of this indel whose deletion is extended by `by` past
+    /// its current end, e.g. to include trailing whitespace.
+    pub fn extend_delete(&self, by: TextSize) -> Indel {
+        self.with_delete(TextRange::new(self.delete.start(), self.delete.end() + by))
+    }
+
+    /// Splits the deletion of this indel at `offset`, returning the indel for
+    /// the part before it, which inserts the text, and the one deleting the
+    /// rest. Returns `None` if `offset` is outside of the deletion.
+    pub fn split_at(&self, offset: TextSize) -> Option<(Indel, Indel)> {
+        if !self.delete.contains_inclusive(offset) {
+            return None;
+        }
+        let first = self.with_delete(TextRange::new(self.delete.start(), offset));
+        Some((first, Indel::delete(TextRange::new(offset, self.delete.end()))))
+    }
+
+    /// Combines this indel and `other` into a single indel covering both,
+    /// keeping the `original` text between them. Returns `None` if the indels
+    /// overlap.
+    pub fn merge_with(&self, other: &Indel, original: &str) -> Option<Indel> {
+        let key = |indel: &Indel| (indel.delete.start(), indel.delete.end());
+        let (first, second) = if key(self) <= key(other) { (self, other) } else { (other, self) };
+        if first.delete.end() > second.delete.start() {
+            return None;
+        }
+        let between =
+            original.get(usize::from(first.delete.end())..second.delete.start().into())?;
+        let insert = format!("{}{}{}", first.insert, between, second.insert);
+        Some(Indel::replace(TextRange::new(first.delete.start(), second.delete.end()), insert))
+    }
+
     pub fn apply(&self, text: &mut String) {
         let start: usize = self.delete.start().into();
         let end: usize = self.delete.end().into();
         text.replace_range(start..end, &self.insert);
     }
+
+    /// Like `apply`, but returns an error instead of panicking if the deleted
+    /// range is past the end of `text` or splits a char.
+    pub fn try_apply(&self, text: &str) -> Result<String, ApplyError> {
+        if let Some(offset) = self.invalid_offset(text) {
+            return Err(ApplyError { index: 0, offset });
+        }
+        let mut res = text.to_string();
+        self.apply(&mut res);
+        Ok(res)
+    }
+
+    /// Returns the first end of the deleted range which is not a char
+    /// boundary of `text`.
+    fn invalid_offset(&self, text: &str) -> Option<TextSize> {
+        let offsets = [self.delete.start(), self.delete.end()];
+        offsets.iter().copied().find(|&offset| !text.is_char_boundary(offset.into()))
+    }
+
+    /// Writes the result of applying this indel to `src` into `dst`, replacing
+    /// its contents but keeping its allocation.
+    pub fn apply_into(&self, src: &str, dst: &mut String) {
+        let start: usize = self.delete.start().into();
+        let end: usize = self.delete.end().into();
+        dst.clear();
+        dst.push_str(&src[..start]);
+        dst.push_str(&self.insert);
+        dst.push_str(&src[end..]);
+    }
 }
 
 impl TextEdit {
@@ -64,6 +213,65 @@ impl TextEdit {
         builder.finish()
     }
 
+    /// Computes an edit turning `old` into `new`. Past their common prefix and
+    /// suffix, the texts are diffed by lines, so only the changed lines are
+    /// replaced.
+    pub fn from_diff(old: &str, new: &str) -> TextEdit {
+        let prefix = common_prefix_len(old, new);
+        let suffix = common_suffix_len(&old[prefix..], &new[prefix..]);
+        let old_mid = &old[prefix..old.len() - suffix];
+        let new_mid = &new[prefix..new.len() - suffix];
+
+        let mut builder = TextEditBuilder::default();
+        let offset = TextSize::of(&old[..prefix]);
+        let old_lines = split_lines(old_mid);
+        let new_lines = split_lines(new_mid);
+        if old_lines.len() * new_lines.len() > MAX_DIFF_LINE_PAIRS {
+            builder.replace(TextRange::at(offset, TextSize::of(old_mid)), new_mid.to_string());
+            return builder.finish();
+        }
+
+        // `lcs[i][j]` is the length of the longest common subsequence of
+        // `old_lines[i..]` and `new_lines[j..]`.
+        let (n, m) = (old_lines.len(), new_lines.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_lines[i] == new_lines[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        let mut old_offset = offset;
+        // Start in `old` and inserted text of the change being built
+        let mut change: Option<(TextSize, String)> = None;
+        while i < n || j < m {
+            if i < n && j < m && old_lines[i] == new_lines[j] {
+                if let Some((start, insert)) = change.take() {
+                    builder.replace(TextRange::new(start, old_offset), insert);
+                }
+                old_offset += TextSize::of(old_lines[i]);
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                change.get_or_insert_with(|| (old_offset, String::new())).1.push_str(new_lines[j]);
+                j += 1;
+            } else {
+                change.get_or_insert_with(|| (old_offset, String::new()));
+                old_offset += TextSize::of(old_lines[i]);
+                i += 1;
+            }
+        }
+        if let Some((start, insert)) = change {
+            builder.replace(TextRange::new(start, old_offset), insert);
+        }
+        builder.finish()
+    }
+
     pub fn len(&self) -> usize {
         self.indels.len()
     }
@@ -72,12 +280,47 @@ impl TextEdit {
         self.indels.is_empty()
     }
 
+    /// Iterates over the indels, sorted by the start of their deletions.
     pub fn iter(&self) -> slice::Iter<'_, Indel> {
         self.indels.iter()
     }
 
-    pub fn into_iter(self) -> vec::IntoIter<Indel> {
-        self.indels.into_iter()
+    /// Iterates over the indels from the last one to the first one, which is
+    /// the order to apply them in one by one.
+    pub fn iter_rev(&self) -> iter::Rev<slice::Iter<'_, Indel>> {
+        self.indels.iter().rev()
+    }
+
+    /// Calls `f` on the insertion of every indel, e.g. to normalize line
+    /// endings. Deletions are left alone, so the indels stay disjoint.
+    pub fn map_inserts(&mut self, mut f: impl FnMut(&mut String)) {
+        self.indels.iter_mut().for_each(|indel| f(&mut indel.insert));
+    }
+
+    /// Removes the LSP snippet syntax, like `$0` and `${1:name}`, from the
+    /// insertions, keeping the default text of the placeholders. This turns a
+    /// snippet edit into a plain one for clients without snippet support.
+    pub fn strip_snippets(&mut self) {
+        self.map_inserts(|insert| *insert = strip_snippet(insert));
+    }
+
+    /// Merges inserts at the same offset into a single insert, concatenating
+    /// their texts in the order they were added.
+    pub fn coalesce_inserts(&mut self) {
+        let mut res: Vec<Indel> = Vec::with_capacity(self.indels.len());
+        for indel in self.indels.drain(..) {
+            match res.last_mut() {
+                Some(prev)
+                    if prev.is_insert()
+                        && indel.is_insert()
+                        && prev.delete.start() == indel.delete.start() =>
+                {
+                    prev.insert.push_str(&indel.insert)
+                }
+                _ => res.push(indel),
+            }
+        }
+        self.indels = res;
     }
 
     pub fn apply(&self, text: &mut String) {
@@ -114,6 +357,54 @@ impl TextEdit {
         *text = buf
     }
 
+    /// Returns the smallest range of the original text containing every
+    /// deletion, or `None` for an empty edit. Insertions count as the empty
+    /// range at their offset.
+    pub fn covering_range(&self) -> Option<TextRange> {
+        let first = self.indels.first()?;
+        let last = self.indels.last()?;
+        Some(TextRange::new(first.delete.start(), last.delete.end()))
+    }
+
+    /// Returns by how many bytes applying this edit grows the text, negative
+    /// if it shrinks it.
+    pub fn len_delta(&self) -> i64 {
+        self.indels
+            .iter()
+            .map(|indel| indel.insert.len() as i64 - usize::from(indel.delete.len()) as i64)
+            .sum()
+    }
+
+    /// Applies the edit to `text`, also returning the range covering all the
+    /// changes in the new text. The range is `None` for an empty edit.
+    pub fn apply_tracking_changes(&self, mut text: String) -> (String, Option<TextRange>) {
+        let (first, last) = match (self.indels.first(), self.indels.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return (text, None),
+        };
+        self.apply(&mut text);
+        let mut end = last.delete.start() + TextSize::of(&last.insert);
+        for indel in self.indels[..self.indels.len() - 1].iter() {
+            end = end + TextSize::of(&indel.insert) - indel.delete.len();
+        }
+        (text, Some(TextRange::new(first.delete.start(), end)))
+    }
+
+    /// Like `apply`, but checks every indel against `text` first, instead of
+    /// panicking on the first bad one.
+    pub fn try_apply(&self, text: &str) -> Result<String, ApplyError> {
+        for (index, indel) in self.indels.iter().enumerate() {
+            if let Some(offset) = indel.invalid_offset(text) {
+                return Err(ApplyError { index, offset });
+            }
+        }
+        let mut res = text.to_string();
+        for indel in self.iter_rev() {
+            indel.apply(&mut res);
+        }
+        Ok(res)
+    }
+
     pub fn union(&mut self, other: TextEdit) -> Result<(), TextEdit> {
         // FIXME: can be done without allocating intermediate vector
         let mut all = self.iter().chain(other.iter()).collect::<Vec<_>>();
@@ -125,6 +416,127 @@ impl TextEdit {
         Ok(())
     }
 
+    /// Combines two edits of the same text like `union`, but instead of failing
+    /// on a conflict, drops every indel of `a` which overlaps one of `b`, so
+    /// that the overlapping span only gets `b`'s content.
+    pub fn merge_preferring_last(a: TextEdit, b: TextEdit) -> TextEdit {
+        let overlaps = |l: &Indel, r: &Indel| {
+            l.delete.end() > r.delete.start() && r.delete.end() > l.delete.start()
+        };
+        let mut indels = a
+            .indels
+            .into_iter()
+            .filter(|indel| !b.indels.iter().any(|it| overlaps(indel, it)))
+            .collect::<Vec<_>>();
+        indels.extend(b.indels);
+        assert!(check_disjoint(&mut indels));
+        TextEdit { indels }
+    }
+
+    /// Composes this edit with `other`, whose offsets refer to the text after
+    /// applying this edit. Applying the result to the original text is the same
+    /// as applying `self` and then `other`. Text inserted by `self` and deleted
+    /// by `other` is dropped from the inserts instead of deleted from the text.
+    pub fn compose(&self, other: &TextEdit) -> TextEdit {
+        // The text in between the two edits, as the parts of the original text
+        // it keeps and the texts inserted by `self`.
+        let mut pieces = Vec::new();
+        let mut len = TextSize::from(0);
+        let mut tail = TextSize::from(0);
+        for indel in self.indels.iter() {
+            if indel.delete.start() > tail {
+                pieces.push(Piece::Keep(TextRange::new(tail, indel.delete.start())));
+                len += indel.delete.start() - tail;
+            }
+            if !indel.insert.is_empty() {
+                pieces.push(Piece::Insert(indel.insert.clone()));
+                len += TextSize::of(&indel.insert);
+            }
+            tail = indel.delete.end();
+        }
+        // Past the last indel of `self` the text is unchanged, so only the part
+        // `other` reaches into is needed.
+        let end = other.indels.iter().map(|it| it.delete.end()).max().unwrap_or(len);
+        if end > len {
+            pieces.push(Piece::Keep(TextRange::at(tail, end - len)));
+            tail += end - len;
+        }
+
+        // Going from the last indel keeps the offsets of the earlier ones valid.
+        for indel in other.iter_rev() {
+            let start = split_pieces(&mut pieces, indel.delete.start());
+            let end = split_pieces(&mut pieces, indel.delete.end());
+            let insert = Some(indel.insert.clone()).filter(|it| !it.is_empty());
+            pieces.splice(start..end, insert.map(Piece::Insert));
+        }
+
+        let mut indels = Vec::new();
+        let mut offset = TextSize::from(0);
+        let mut insert = String::new();
+        for piece in pieces {
+            match piece {
+                Piece::Insert(text) => insert.push_str(&text),
+                Piece::Keep(range) => {
+                    if range.start() > offset || !insert.is_empty() {
+                        let delete = TextRange::new(offset, range.start());
+                        indels.push(Indel::replace(delete, mem::take(&mut insert)));
+                    }
+                    offset = range.end();
+                }
+            }
+        }
+        if tail > offset || !insert.is_empty() {
+            indels.push(Indel::replace(TextRange::new(offset, tail), insert));
+        }
+        TextEdit { indels }
+    }
+
+    /// Returns the edit undoing this one, given the `original` text this edit
+    /// applies to. The offsets of the result refer to the edited text.
+    pub fn invert(&self, original: &str) -> TextEdit {
+        let mut offset = TextSize::from(0);
+        let mut prev = TextSize::from(0);
+        let mut indels = Vec::with_capacity(self.indels.len());
+        for indel in self.indels.iter() {
+            offset += indel.delete.start() - prev;
+            let delete = TextRange::at(offset, TextSize::of(&indel.insert));
+            indels.push(Indel::replace(delete, original[indel.delete].to_string()));
+            offset = delete.end();
+            prev = indel.delete.end();
+        }
+        TextEdit { indels }
+    }
+
+    /// Rebases `self`, an edit of the same text as `applied`, onto the text
+    /// produced by `applied`, shifting its indels past the changes there.
+    /// Returns `None` if the edits conflict, that is if they change overlapping
+    /// ranges or one inserts inside a range changed by the other. Inserts at
+    /// the same offset are placed after those of `applied`.
+    pub fn rebase(&self, applied: &TextEdit) -> Option<TextEdit> {
+        let conflicts = |it: &Indel, other: &Indel| {
+            it.delete.end() > other.delete.start() && other.delete.end() > it.delete.start()
+        };
+        let mut indels = Vec::with_capacity(self.indels.len());
+        for indel in self.indels.iter() {
+            if applied.indels.iter().any(|other| conflicts(indel, other)) {
+                return None;
+            }
+            let start = applied.map_offset(indel.delete.start());
+            indels.push(indel.with_delete(TextRange::at(start, indel.delete.len())));
+        }
+        Some(TextEdit { indels })
+    }
+
+    /// Splits the edit into the indels before `offset` and those at or after
+    /// it. An indel straddling `offset` goes to the side containing its start,
+    /// so only indels starting before `offset` end up on the left.
+    pub fn split_at(self, offset: TextSize) -> (TextEdit, TextEdit) {
+        let mut indels = self.indels;
+        let mid = indels.iter().position(|it| it.delete.start() >= offset).unwrap_or(indels.len());
+        let right = indels.split_off(mid);
+        (TextEdit { indels }, TextEdit { indels: right })
+    }
+
     pub fn apply_to_offset(&self, offset: TextSize) -> Option<TextSize> {
         let mut res = offset;
         for indel in self.indels.iter() {
@@ -139,6 +551,44 @@ impl TextEdit {
         }
         Some(res)
     }
+
+    /// Returns where `offset` in the original text lands after applying the
+    /// edit. Unlike `apply_to_offset`, an offset in a deleted range is clamped
+    /// to the start of its replacement, and an offset at an insertion point ends
+    /// up after the inserted text.
+    pub fn map_offset(&self, offset: TextSize) -> TextSize {
+        let mut res = offset;
+        for indel in self.indels.iter() {
+            if indel.delete.start() > offset {
+                break;
+            }
+            if offset < indel.delete.end() {
+                return res - (offset - indel.delete.start());
+            }
+            res += TextSize::of(&indel.insert);
+            res -= indel.delete.len();
+        }
+        res
+    }
+}
+
+impl IntoIterator for TextEdit {
+    type Item = Indel;
+    type IntoIter = vec::IntoIter<Indel>;
+
+    /// Iterates over the indels, sorted by the start of their deletions.
+    fn into_iter(self) -> vec::IntoIter<Indel> {
+        self.indels.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TextEdit {
+    type Item = &'a Indel;
+    type IntoIter = slice::Iter<'a, Indel>;
+
+    fn into_iter(self) -> slice::Iter<'a, Indel> {
+        self.iter()
+    }
 }
 
 impl TextEditBuilder {
@@ -152,15 +602,139 @@ impl TextEditBuilder {
         self.indels.push(Indel::insert(offset, text))
     }
     pub fn finish(self) -> TextEdit {
+        self.try_finish().unwrap_or_else(|err| panic!("{}", err))
+    }
+    /// Like `finish`, but returns an error instead of panicking if some of the
+    /// indels overlap.
+    pub fn try_finish(self) -> Result<TextEdit, OverlapError> {
         let mut indels = self.indels;
-        assert!(check_disjoint(&mut indels));
-        TextEdit { indels }
+        indels.sort_by_key(|indel| (indel.delete.start(), indel.delete.end()));
+        for (l, r) in indels.iter().zip(indels.iter().skip(1)) {
+            if l.delete.end() > r.delete.start() {
+                return Err(OverlapError { first: l.delete, second: r.delete });
+            }
+        }
+        Ok(TextEdit { indels })
     }
     pub fn invalidates_offset(&self, offset: TextSize) -> bool {
         self.indels.iter().any(|indel| indel.delete.contains_inclusive(offset))
     }
 }
 
+/// A part of the text in between two edits, see `TextEdit::compose`.
+enum Piece {
+    /// A range of the original text which is kept
+    Keep(TextRange),
+    /// A text which is inserted
+    Insert(String),
+}
+
+impl Piece {
+    fn len(&self) -> TextSize {
+        match self {
+            Piece::Keep(range) => range.len(),
+            Piece::Insert(text) => TextSize::of(text),
+        }
+    }
+}
+
+/// Splits the piece containing `offset` in two, returning the index of the
+/// first piece after `offset`.
+fn split_pieces(pieces: &mut Vec<Piece>, offset: TextSize) -> usize {
+    let mut start = TextSize::from(0);
+    for i in 0..pieces.len() {
+        if start == offset {
+            return i;
+        }
+        let end = start + pieces[i].len();
+        if offset < end {
+            let at = offset - start;
+            let (left, right) = match &pieces[i] {
+                Piece::Keep(range) => {
+                    let mid = range.start() + at;
+                    (
+                        Piece::Keep(TextRange::new(range.start(), mid)),
+                        Piece::Keep(TextRange::new(mid, range.end())),
+                    )
+                }
+                Piece::Insert(text) => {
+                    let (left, right) = text.split_at(at.into());
+                    (Piece::Insert(left.to_string()), Piece::Insert(right.to_string()))
+                }
+            };
+            pieces[i] = left;
+            pieces.insert(i + 1, right);
+            return i + 1;
+        }
+        start = end;
+    }
+    pieces.len()
+}
+
+/// Above this many pairs of lines, `TextEdit::from_diff` replaces all of the
+/// lines in between the common prefix and suffix instead of diffing them.
+const MAX_DIFF_LINE_PAIRS: usize = 1 << 20;
+
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum()
+}
+
+fn common_suffix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum()
+}
+
+/// Splits `text` into lines, keeping their newlines.
+fn split_lines(text: &str) -> Vec<&str> {
+    let mut res = Vec::new();
+    let mut start = 0;
+    for (idx, _) in text.match_indices('\n') {
+        res.push(&text[start..=idx]);
+        start = idx + 1;
+    }
+    if start < text.len() {
+        res.push(&text[start..]);
+    }
+    res
+}
+
+/// Removes the tab stops and placeholders from the snippet `text`, unescaping
+/// the rest.
+fn strip_snippet(mut text: &str) -> String {
+    let digits = |text: &str| text.bytes().take_while(u8::is_ascii_digit).count();
+    let mut res = String::with_capacity(text.len());
+    let mut depth = 0;
+    while let Some(c) = text.chars().next() {
+        let mut len = c.len_utf8();
+        match c {
+            '\\' if text[1..].starts_with(&['$', '}', '\\'][..]) => {
+                res.push_str(&text[1..2]);
+                len = 2;
+            }
+            '$' if digits(&text[1..]) > 0 => len += digits(&text[1..]),
+            '$' if text[1..].starts_with('{') && digits(&text[2..]) > 0 => {
+                let end = 2 + digits(&text[2..]);
+                match text[end..].chars().next() {
+                    Some('}') => len = end + 1,
+                    Some(':') => {
+                        len = end + 1;
+                        depth += 1;
+                    }
+                    _ => res.push(c),
+                }
+            }
+            '}' if depth > 0 => depth -= 1,
+            _ => res.push(c),
+        }
+        text = &text[len..];
+    }
+    res
+}
+
 fn check_disjoint(indels: &mut [impl std::borrow::Borrow<Indel>]) -> bool {
     indels.sort_by_key(|indel| (indel.borrow().delete.start(), indel.borrow().delete.end()));
     indels
@@ -168,3 +742,488 @@ fn check_disjoint(indels: &mut [impl std::borrow::Borrow<Indel>]) -> bool {
         .zip(indels.iter().skip(1))
         .all(|(l, r)| l.borrow().delete.end() <= r.borrow().delete.start())
 }
+
+#[cfg(test)]
+mod proptests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: u32, end: u32) -> TextRange {
+        TextRange::new(start.into(), end.into())
+    }
+
+    #[test]
+    fn test_extend_delete() {
+        let mut text = "fn foo() {}\nfn bar() {}\n".to_string();
+        let indel = Indel::delete(range(0, 11)).extend_delete(1.into());
+        assert_eq!(indel.delete, range(0, 12));
+        indel.apply(&mut text);
+        assert_eq!(text, "fn bar() {}\n");
+
+        let indel = Indel::replace(range(3, 6), "baz".to_string()).with_delete(range(3, 8));
+        assert_eq!(indel.insert, "baz");
+        assert_eq!(indel.delete, range(3, 8));
+    }
+
+    #[test]
+    fn test_indel_hash() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(Indel::replace(range(1, 3), "foo".to_string()));
+        set.insert(Indel::replace(range(1, 3), "foo".to_string()));
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&Indel::replace(range(1, 3), "foo".to_string())));
+        assert!(!set.contains(&Indel::replace(range(1, 3), "bar".to_string())));
+    }
+
+    #[test]
+    fn test_indel_kinds() {
+        let kinds = |indel: Indel| (indel.is_insert(), indel.is_delete(), indel.is_replace());
+        assert_eq!(kinds(Indel::insert(3.into(), "foo".to_string())), (true, false, false));
+        assert_eq!(kinds(Indel::delete(range(3, 6))), (false, true, false));
+        assert_eq!(kinds(Indel::replace(range(3, 6), "foo".to_string())), (false, false, true));
+        assert_eq!(kinds(Indel::insert(3.into(), String::new())), (true, true, false));
+    }
+
+    #[test]
+    fn test_indel_split_at() {
+        let indel = Indel::replace(range(2, 6), "foo".to_string());
+        let (first, second) = indel.split_at(4.into()).unwrap();
+        assert_eq!(first, Indel::replace(range(2, 4), "foo".to_string()));
+        assert_eq!(second, Indel::delete(range(4, 6)));
+        assert_eq!(indel.split_at(6.into()).unwrap().1, Indel::delete(range(6, 6)));
+        assert_eq!(indel.split_at(1.into()), None);
+        assert_eq!(indel.split_at(7.into()), None);
+
+        let indel = Indel::insert(3.into(), "bar".to_string());
+        assert_eq!(indel.split_at(3.into()), Some((indel.clone(), Indel::delete(range(3, 3)))));
+        assert_eq!(indel.split_at(4.into()), None);
+    }
+
+    #[test]
+    fn test_merge_with() {
+        let text = "fn foo(a: i32) {}";
+        let insert = Indel::insert(3.into(), "pub_".to_string());
+        let delete = Indel::delete(range(6, 7));
+        let merged = delete.merge_with(&insert, text).unwrap();
+        assert_eq!(merged.delete, range(3, 7));
+        assert_eq!(merged.insert, "pub_foo");
+
+        let mut actual = text.to_string();
+        merged.apply(&mut actual);
+        let mut expected = text.to_string();
+        delete.apply(&mut expected);
+        insert.apply(&mut expected);
+        assert_eq!(actual, expected);
+
+        assert!(delete.merge_with(&Indel::delete(range(5, 8)), text).is_none());
+    }
+
+    #[test]
+    fn test_apply_into() {
+        let src = "fn foo() {}";
+        let mut dst = String::with_capacity(64);
+        let capacity = dst.capacity();
+        for indel in [
+            Indel::insert(3.into(), "bar_".to_string()),
+            Indel::delete(range(3, 6)),
+            Indel::replace(range(9, 11), "{ 92 }".to_string()),
+        ]
+        .iter()
+        {
+            let mut expected = src.to_string();
+            indel.apply(&mut expected);
+            indel.apply_into(src, &mut dst);
+            assert_eq!(dst, expected);
+            assert_eq!(dst.capacity(), capacity);
+        }
+    }
+
+    #[test]
+    fn test_split_at() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(0, 2), "a".to_string());
+        builder.delete(range(4, 6));
+        builder.insert(6.into(), "b".to_string());
+        builder.replace(range(8, 12), "c".to_string());
+        let edit = builder.finish();
+
+        let (left, right) = edit.clone().split_at(6.into());
+        let ranges = |edit: &TextEdit| edit.iter().map(|it| it.delete).collect::<Vec<_>>();
+        assert_eq!(ranges(&left), vec![range(0, 2), range(4, 6)]);
+        assert_eq!(ranges(&right), vec![range(6, 6), range(8, 12)]);
+
+        let (left, right) = edit.split_at(10.into());
+        assert_eq!(ranges(&left), vec![range(0, 2), range(4, 6), range(6, 6), range(8, 12)]);
+        assert!(right.is_empty());
+    }
+
+    #[test]
+    fn test_coalesce_inserts() {
+        let mut builder = TextEditBuilder::default();
+        builder.insert(5.into(), "foo".to_string());
+        builder.replace(range(5, 7), "baz".to_string());
+        builder.insert(5.into(), "(".to_string());
+        builder.insert(2.into(), "quux".to_string());
+        builder.insert(5.into(), ")".to_string());
+        let mut edit = builder.finish();
+
+        edit.coalesce_inserts();
+        let indels = edit.iter().map(|it| (it.delete, it.insert.as_str())).collect::<Vec<_>>();
+        assert_eq!(
+            indels,
+            vec![(range(2, 2), "quux"), (range(5, 5), "foo()"), (range(5, 7), "baz")]
+        );
+    }
+
+    #[test]
+    fn test_merge_preferring_last() {
+        let text = "fn foo(a: i32, b: i32) {}";
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "bar".to_string());
+        builder.replace(range(7, 13), "x: u32".to_string());
+        builder.insert(24.into(), " 92 ".to_string());
+        let a = builder.finish();
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(10, 21), "i64, y: i64".to_string());
+        builder.insert(24.into(), "/* */".to_string());
+        let b = builder.finish();
+
+        let edit = TextEdit::merge_preferring_last(a, b);
+        let mut actual = text.to_string();
+        edit.apply(&mut actual);
+        assert_eq!(actual, "fn bar(a: i64, y: i64) { 92 /* */}");
+    }
+
+    #[test]
+    fn test_merge_preferring_last_drops_overlapped() {
+        let text = "let x = foo(1, 2);";
+        let a = TextEdit::replace(range(8, 17), "bar(92)".to_string());
+        let b = TextEdit::replace(range(12, 13), "3".to_string());
+
+        let edit = TextEdit::merge_preferring_last(a, b);
+        let indels = edit.iter().map(|it| (it.delete, it.insert.as_str())).collect::<Vec<_>>();
+        assert_eq!(indels, vec![(range(12, 13), "3")]);
+        let mut actual = text.to_string();
+        edit.apply(&mut actual);
+        assert_eq!(actual, "let x = foo(3, 2);");
+    }
+
+    #[test]
+    fn test_try_apply() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "bar".to_string());
+        builder.insert(10.into(), " 92 ".to_string());
+        let edit = builder.finish();
+        assert_eq!(edit.try_apply("fn foo() {}"), Ok("fn bar() { 92 }".to_string()));
+
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "bar".to_string());
+        builder.delete(range(10, 20));
+        let edit = builder.finish();
+        assert_eq!(edit.try_apply("fn foo() {}"), Err(ApplyError { index: 1, offset: 20.into() }));
+        assert_eq!(edit.try_apply("fn мир() {}"), Err(ApplyError { index: 0, offset: 6.into() }));
+    }
+
+    #[test]
+    fn test_indel_try_apply() {
+        let text = "let メモ = 92;";
+        let indel = Indel::replace(range(4, 10), "memo".to_string());
+        assert_eq!(indel.try_apply(text), Ok("let memo = 92;".to_string()));
+        let indel = Indel::replace(range(4, 8), "memo".to_string());
+        assert_eq!(indel.try_apply(text), Err(ApplyError { index: 0, offset: 8.into() }));
+        let indel = Indel::delete(range(5, 7));
+        assert_eq!(indel.try_apply(text), Err(ApplyError { index: 0, offset: 5.into() }));
+        let indel = Indel::insert(92.into(), "memo".to_string());
+        assert_eq!(indel.try_apply(text), Err(ApplyError { index: 0, offset: 92.into() }));
+    }
+
+    #[test]
+    fn test_covering_range() {
+        assert_eq!(TextEdit::default().covering_range(), None);
+        assert_eq!(TextEdit::insert(4.into(), "x".to_string()).covering_range(), Some(range(4, 4)));
+
+        let mut builder = TextEditBuilder::default();
+        builder.insert(2.into(), "foo".to_string());
+        builder.delete(range(5, 7));
+        builder.replace(range(10, 12), "bar".to_string());
+        assert_eq!(builder.finish().covering_range(), Some(range(2, 12)));
+    }
+
+    #[test]
+    fn test_len_delta() {
+        assert_eq!(TextEdit::default().len_delta(), 0);
+        assert_eq!(TextEdit::replace(range(1, 6), "ab".to_string()).len_delta(), -3);
+
+        let mut builder = TextEditBuilder::default();
+        builder.insert(0.into(), "foo".to_string());
+        builder.delete(range(2, 3));
+        assert_eq!(builder.finish().len_delta(), 2);
+    }
+
+    #[test]
+    fn test_apply_tracking_changes() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "frobnicate".to_string());
+        builder.insert(10.into(), " 92 ".to_string());
+        let edit = builder.finish();
+
+        let (text, changed) = edit.apply_tracking_changes("fn foo() {}\n".to_string());
+        assert_eq!(text, "fn frobnicate() { 92 }\n");
+        assert_eq!(&text[changed.unwrap()], "frobnicate() { 92 ");
+
+        let (text, changed) = TextEdit::default().apply_tracking_changes("fn foo() {}".to_string());
+        assert_eq!(text, "fn foo() {}");
+        assert_eq!(changed, None);
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(8, 10), "c".to_string());
+        builder.insert(0.into(), "a".to_string());
+        builder.delete(range(3, 5));
+        let edit = builder.finish();
+
+        let expected = vec![range(0, 0), range(3, 5), range(8, 10)];
+        assert_eq!(edit.iter().map(|it| it.delete).collect::<Vec<_>>(), expected);
+        assert_eq!((&edit).into_iter().map(|it| it.delete).collect::<Vec<_>>(), expected);
+        assert_eq!(edit.into_iter().map(|it| it.delete).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_iter_rev() {
+        let mut builder = TextEditBuilder::default();
+        builder.insert(10.into(), " 92 ".to_string());
+        builder.replace(range(3, 6), "bar".to_string());
+        builder.insert(10.into(), "/* */".to_string());
+        builder.delete(range(0, 2));
+        let edit = builder.finish();
+
+        let ranges = edit.iter_rev().map(|it| it.delete).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![range(10, 10), range(10, 10), range(3, 6), range(0, 2)]);
+
+        let mut text = "fn foo() {}".to_string();
+        for indel in edit.iter_rev() {
+            indel.apply(&mut text);
+        }
+        let mut expected = "fn foo() {}".to_string();
+        edit.apply(&mut expected);
+        assert_eq!(text, expected);
+        assert_eq!(text, " bar() { 92 /* */}");
+    }
+
+    #[test]
+    fn test_compose() {
+        let text = "fn foo() {}";
+        let check = |first: TextEdit, second: TextEdit, expected: &str| {
+            let mut actual = text.to_string();
+            first.compose(&second).apply(&mut actual);
+            assert_eq!(actual, expected);
+            let mut sequential = text.to_string();
+            first.apply(&mut sequential);
+            second.apply(&mut sequential);
+            assert_eq!(actual, sequential);
+        };
+
+        // Insert then delete part of the inserted text and the original text.
+        let first = TextEdit::insert(3.into(), "bar_".to_string());
+        let second = TextEdit::delete(range(5, 10));
+        let composed = first.compose(&second);
+        let indels = composed.iter().map(|it| (it.delete, it.insert.as_str())).collect::<Vec<_>>();
+        assert_eq!(indels, vec![(range(3, 6), "ba")]);
+        check(first, second, "fn ba() {}");
+
+        // Deleting all of the inserted text drops the insert.
+        let first = TextEdit::insert(3.into(), "bar_".to_string());
+        let second = TextEdit::delete(range(3, 7));
+        assert!(first.compose(&second).is_empty());
+
+        // Delete then insert at the same offset.
+        let first = TextEdit::delete(range(3, 6));
+        let second = TextEdit::insert(3.into(), "baz".to_string());
+        let composed = first.compose(&second);
+        let indels = composed.iter().map(|it| (it.delete, it.insert.as_str())).collect::<Vec<_>>();
+        assert_eq!(indels, vec![(range(3, 6), "baz")]);
+        check(first, second, "fn baz() {}");
+
+        // Disjoint edits, with the second one past the end of the first one.
+        let first = TextEdit::replace(range(0, 2), "pub fn".to_string());
+        let second = TextEdit::insert(14.into(), " 92 ".to_string());
+        check(first, second, "pub fn foo() { 92 }");
+    }
+
+    #[test]
+    fn test_invert() {
+        let original = "fn foo(a: i32) {}";
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "frobnicate".to_string());
+        builder.delete(range(7, 13));
+        builder.insert(13.into(), "b: u32".to_string());
+        builder.insert(16.into(), " 92 ".to_string());
+        let edit = builder.finish();
+
+        let mut text = original.to_string();
+        edit.apply(&mut text);
+        assert_eq!(text, "fn frobnicate(b: u32) { 92 }");
+        let inverse = edit.invert(original);
+        let ranges = inverse.iter().map(|it| it.delete).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![range(3, 13), range(14, 14), range(14, 20), range(23, 27)]);
+        inverse.apply(&mut text);
+        assert_eq!(text, original);
+    }
+
+    #[test]
+    fn test_rebase() {
+        let text = "hello world";
+        let applied = TextEdit::replace(range(0, 5), "goodbye".to_string());
+        let mut builder = TextEditBuilder::default();
+        builder.insert(5.into(), ",".to_string());
+        builder.replace(range(6, 11), "there".to_string());
+        let edit = builder.finish();
+
+        let mut actual = text.to_string();
+        applied.apply(&mut actual);
+        edit.rebase(&applied).unwrap().apply(&mut actual);
+        assert_eq!(actual, "goodbye, there");
+
+        assert!(TextEdit::delete(range(3, 7)).rebase(&applied).is_none());
+        assert!(TextEdit::insert(2.into(), "x".to_string()).rebase(&applied).is_none());
+        assert!(applied.rebase(&TextEdit::insert(2.into(), "x".to_string())).is_none());
+    }
+
+    #[test]
+    fn test_map_offset() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "bar_baz".to_string());
+        builder.delete(range(7, 9));
+        builder.insert(16.into(), "92".to_string());
+        builder.insert(16.into(), "!".to_string());
+        let edit = builder.finish();
+        let mut text = "fn foo(a: i32) {}".to_string();
+        edit.apply(&mut text);
+        assert_eq!(text, "fn bar_baz( i32) {92!}");
+
+        let map = |offset: u32| u32::from(edit.map_offset(offset.into()));
+        assert_eq!(map(0), 0);
+        assert_eq!(map(3), 3);
+        assert_eq!(map(5), 3);
+        assert_eq!(map(6), 10);
+        assert_eq!(map(7), 11);
+        assert_eq!(map(8), 11);
+        assert_eq!(map(9), 11);
+        assert_eq!(map(16), 21);
+        assert_eq!(map(17), 22);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut builder = TextEditBuilder::default();
+        builder.replace(range(3, 6), "bar".to_string());
+        builder.insert(10.into(), " 92 ".to_string());
+        let edit = builder.finish();
+
+        let json = serde_json::to_string(&edit).unwrap();
+        assert_eq!(json, r#"[{"insert":"bar","delete":[3,6]},{"insert":" 92 ","delete":[10,10]}]"#);
+        let edit: TextEdit = serde_json::from_str(&json).unwrap();
+        let indels = edit.iter().map(|it| (it.delete, it.insert.as_str())).collect::<Vec<_>>();
+        assert_eq!(indels, vec![(range(3, 6), "bar"), (range(10, 10), " 92 ")]);
+
+        let overlapping = r#"[{"insert":"","delete":[3,6]},{"insert":"","delete":[5,8]}]"#;
+        assert!(serde_json::from_str::<TextEdit>(overlapping).is_err());
+        let unsorted = r#"[{"insert":"","delete":[10,10]},{"insert":"","delete":[3,6]}]"#;
+        assert!(serde_json::from_str::<TextEdit>(unsorted).is_err());
+    }
+
+    #[test]
+    fn test_from_diff() {
+        let check = |old: &str, new: &str, expected: Vec<(TextRange, &str)>| {
+            let edit = TextEdit::from_diff(old, new);
+            let mut actual = old.to_string();
+            edit.apply(&mut actual);
+            assert_eq!(actual, new);
+            let indels = edit.iter().map(|it| (it.delete, it.insert.as_str())).collect::<Vec<_>>();
+            assert_eq!(indels, expected);
+        };
+
+        check("fn foo() {}", "fn foo() {}", vec![]);
+        // Insert only
+        check("fn foo() {}", "fn foo() { 92 }", vec![(range(10, 10), " 92 ")]);
+        // Delete only
+        check("fn foo(a: i32) {}", "fn foo() {}", vec![(range(7, 13), "")]);
+        // Interior replacement
+        check("let мир = 1;", "let world = 1;", vec![(range(4, 10), "world")]);
+        check(
+            "fn main() {\n    foo();\n    bar();\n    baz();\n}\n",
+            "fn main() {\n    quux();\n    bar();\n    baz(92);\n}\n",
+            vec![(range(16, 23), "quux();\n"), (range(34, 42), "    baz(92")],
+        );
+    }
+
+    #[test]
+    fn test_try_finish() {
+        let mut builder = TextEditBuilder::default();
+        builder.delete(range(10, 15));
+        builder.delete(range(5, 10));
+        let edit = builder.try_finish().unwrap();
+        let ranges = edit.iter().map(|it| it.delete).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![range(5, 10), range(10, 15)]);
+
+        let mut builder = TextEditBuilder::default();
+        builder.insert(10.into(), "92".to_string());
+        builder.replace(range(8, 12), "foo".to_string());
+        let err = builder.try_finish().unwrap_err();
+        assert_eq!(err, OverlapError { first: range(8, 12), second: range(10, 10) });
+    }
+
+    #[test]
+    fn test_apply_many_indels() {
+        let text = "fn foo() { 92 }\n".repeat(5_000);
+        let mut builder = TextEditBuilder::default();
+        for line in 0..5_000u32 {
+            let start = line * 16;
+            builder.replace(range(start + 3, start + 6), format!("bar_{}", line));
+            builder.delete(range(start + 11, start + 14));
+            builder.insert((start + 15).into(), ";".to_string());
+        }
+        let edit = builder.finish();
+
+        let mut actual = text.clone();
+        edit.apply(&mut actual);
+        let mut expected = text;
+        for indel in edit.iter_rev() {
+            indel.apply(&mut expected);
+        }
+        assert_eq!(actual, expected);
+        assert!(actual.starts_with("fn bar_0() { };\nfn bar_1() { };\n"));
+    }
+
+    #[test]
+    fn test_map_inserts() {
+        let mut builder = TextEditBuilder::default();
+        builder.insert(0.into(), "fn foo() {\n}\n".to_string());
+        builder.replace(range(3, 6), "\n    bar\n".to_string());
+        builder.delete(range(8, 10));
+        let mut edit = builder.finish();
+
+        edit.map_inserts(|insert| *insert = insert.replace('\n', "\r\n"));
+        let inserts = edit.iter().map(|it| it.insert.as_str()).collect::<Vec<_>>();
+        assert_eq!(inserts, vec!["fn foo() {\r\n}\r\n", "\r\n    bar\r\n", ""]);
+        let ranges = edit.iter().map(|it| it.delete).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![range(0, 0), range(3, 6), range(8, 10)]);
+    }
+
+    #[test]
+    fn test_strip_snippets() {
+        let mut builder = TextEditBuilder::default();
+        builder.insert(0.into(), "fn ${1:foo}() {\n    $0\n}\n".to_string());
+        builder.replace(range(3, 6), "${1:Vec<${2:T}>} \\$0 ${3} $ {}".to_string());
+        let mut edit = builder.finish();
+
+        edit.strip_snippets();
+        let inserts = edit.iter().map(|it| it.insert.as_str()).collect::<Vec<_>>();
+        assert_eq!(inserts, vec!["fn foo() {\n    \n}\n", "Vec<T> $0  $ {}"]);
+    }
+}
@@ -0,0 +1,140 @@
+//! Property tests for `TextEdit`, checking its operations against naive
+//! reimplementations on random texts and edits.
+use proptest::prelude::*;
+
+use crate::{TextEdit, TextEditBuilder, TextRange, TextSize};
+
+fn arb_text() -> impl Strategy<Value = String> {
+    "[ab \n\u{e9}\u{1f600}]{0,32}"
+}
+
+/// Generates a text together with a valid edit of it.
+fn arb_text_with_edit() -> impl Strategy<Value = (String, TextEdit)> {
+    arb_text().prop_flat_map(arb_edit)
+}
+
+/// Generates a valid edit of `text`, returned alongside it.
+fn arb_edit(text: String) -> impl Strategy<Value = (String, TextEdit)> {
+    let boundaries = char_boundaries(&text);
+    let indel = (0..boundaries.len(), 0..boundaries.len(), "[xy\n\u{1f600}]{0,3}");
+    (Just(text), Just(boundaries), proptest::collection::vec(indel, 0..5)).prop_map(
+        |(text, boundaries, indels)| {
+            let mut indels = indels
+                .into_iter()
+                .map(|(a, b, insert)| {
+                    let range = TextRange::new(boundaries[a.min(b)], boundaries[a.max(b)]);
+                    (range, insert)
+                })
+                .collect::<Vec<_>>();
+            indels.sort_by_key(|(range, _)| (range.start(), range.end()));
+
+            // Drop the indels overlapping an earlier one.
+            let mut builder = TextEditBuilder::default();
+            let mut end = TextSize::from(0);
+            for (range, insert) in indels {
+                if range.start() >= end {
+                    end = range.end();
+                    builder.replace(range, insert);
+                }
+            }
+            (text, builder.finish())
+        },
+    )
+}
+
+fn char_boundaries(text: &str) -> Vec<TextSize> {
+    (0..=text.len())
+        .filter(|&it| text.is_char_boundary(it))
+        .map(|it| TextSize::from(it as u32))
+        .collect()
+}
+
+/// Applies the indels of `edit` one by one, starting from the last one so
+/// that the offsets of the others stay valid.
+fn apply_naive(edit: &TextEdit, text: &str) -> String {
+    let mut res = text.to_string();
+    for indel in edit.iter_rev() {
+        indel.apply(&mut res);
+    }
+    res
+}
+
+proptest! {
+    #[test]
+    fn apply_matches_naive((text, edit) in arb_text_with_edit()) {
+        let expected = apply_naive(&edit, &text);
+
+        let mut actual = text.clone();
+        edit.apply(&mut actual);
+        prop_assert_eq!(&actual, &expected);
+        prop_assert_eq!(edit.try_apply(&text), Ok(expected.clone()));
+        let (actual, _) = edit.apply_tracking_changes(text);
+        prop_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn apply_to_offset_matches_prefix((text, edit) in arb_text_with_edit()) {
+        for offset in char_boundaries(&text) {
+            let inside_delete =
+                edit.iter().any(|it| it.delete.start() < offset && offset < it.delete.end());
+            let expected = if inside_delete {
+                None
+            } else {
+                // The offset lands at the end of the edited text before it.
+                let mut prefix = text[..usize::from(offset)].to_string();
+                for indel in edit.iter().rev().filter(|it| it.delete.start() < offset) {
+                    indel.apply(&mut prefix);
+                }
+                Some(TextSize::of(&prefix))
+            };
+            prop_assert_eq!(edit.apply_to_offset(offset), expected);
+        }
+    }
+
+    #[test]
+    fn len_delta_matches_apply((text, edit) in arb_text_with_edit()) {
+        let edited = apply_naive(&edit, &text);
+        prop_assert_eq!(edited.len() as i64 - text.len() as i64, edit.len_delta());
+    }
+
+    #[test]
+    fn split_at_preserves_result((text, edit) in arb_text_with_edit()) {
+        let expected = apply_naive(&edit, &text);
+        let boundaries = char_boundaries(&text);
+        let offset = boundaries[boundaries.len() / 2];
+
+        let (left, right) = edit.split_at(offset);
+        let actual = apply_naive(&left, &apply_naive(&right, &text));
+        prop_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn coalesce_inserts_preserves_result((text, mut edit) in arb_text_with_edit()) {
+        let expected = apply_naive(&edit, &text);
+        edit.coalesce_inserts();
+        prop_assert_eq!(apply_naive(&edit, &text), expected);
+    }
+
+    #[test]
+    fn invert_restores_original((text, edit) in arb_text_with_edit()) {
+        let edited = apply_naive(&edit, &text);
+        prop_assert_eq!(apply_naive(&edit.invert(&text), &edited), text);
+    }
+
+    #[test]
+    fn from_diff_matches_new((text, edit) in arb_text_with_edit()) {
+        let new = apply_naive(&edit, &text);
+        prop_assert_eq!(apply_naive(&TextEdit::from_diff(&text, &new), &text), new);
+    }
+
+    #[test]
+    fn compose_matches_sequential(
+        (text, first, (mid, second)) in arb_text_with_edit().prop_flat_map(|(text, first)| {
+            let mid = apply_naive(&first, &text);
+            (Just(text), Just(first), arb_edit(mid))
+        })
+    ) {
+        let expected = apply_naive(&second, &mid);
+        prop_assert_eq!(apply_naive(&first.compose(&second), &text), expected);
+    }
+}
@@ -11,7 +11,7 @@ use std::{ffi::OsString, path::PathBuf};
 
 use lsp_types::ClientCapabilities;
 use ra_flycheck::FlycheckConfig;
-use ra_ide::{AssistConfig, CompletionConfig, InlayHintsConfig};
+use ra_ide::{AssistConfig, CompletionConfig, HighlightConfig, InlayHintsConfig};
 use ra_project_model::CargoConfig;
 use serde::Deserialize;
 
@@ -31,6 +31,7 @@ pub struct Config {
     pub check: Option<FlycheckConfig>,
 
     pub inlay_hints: InlayHintsConfig,
+    pub highlighting: HighlightConfig,
     pub completion: CompletionConfig,
     pub assist: AssistConfig,
     pub call_info_full: bool,
@@ -132,6 +133,7 @@ impl Default for Config {
                 chaining_hints: true,
                 max_length: None,
             },
+            highlighting: HighlightConfig::default(),
             completion: CompletionConfig {
                 enable_postfix_completions: true,
                 add_call_parenthesis: true,
@@ -225,6 +227,15 @@ impl Config {
         set(value, "/inlayHints/parameterHints", &mut self.inlay_hints.parameter_hints);
         set(value, "/inlayHints/chainingHints", &mut self.inlay_hints.chaining_hints);
         set(value, "/inlayHints/maxLength", &mut self.inlay_hints.max_length);
+        set(value, "/highlighting/redundantClone", &mut self.highlighting.redundant_clone);
+        set(value, "/highlighting/objectSafety", &mut self.highlighting.object_safety);
+        set(value, "/highlighting/fallibleFunctions", &mut self.highlighting.fallible_functions);
+        set(value, "/highlighting/namingCase", &mut self.highlighting.naming_case);
+        set(value, "/highlighting/smartPointers", &mut self.highlighting.smart_pointers);
+        set(value, "/highlighting/docMarkdown", &mut self.highlighting.doc_markdown);
+        set(value, "/highlighting/markerTypes", &mut self.highlighting.marker_types);
+        set(value, "/highlighting/dropTypes", &mut self.highlighting.drop_types);
+        set(value, "/highlighting/builtinMethods", &mut self.highlighting.builtin_methods);
         set(value, "/completion/postfix/enable", &mut self.completion.enable_postfix_completions);
         set(value, "/completion/addCallParenthesis", &mut self.completion.add_call_parenthesis);
         set(value, "/completion/addCallArgumentSnippets", &mut self.completion.add_call_argument_snippets);
@@ -45,12 +45,15 @@ define_semantic_token_types![
     (UNION, "union"),
     (UNRESOLVED_REFERENCE, "unresolvedReference"),
     (FORMAT_SPECIFIER, "formatSpecifier"),
+    (PUNCTUATION, "punctuation"),
 ];
 
 macro_rules! define_semantic_token_modifiers {
     ($(($ident:ident, $string:literal)),*$(,)?) => {
         $(pub(crate) const $ident: SemanticTokenModifier = SemanticTokenModifier::new($string);)*
 
+        // The modifiers of a token are sent as a `u32` bitset, so at most 32
+        // are supported.
         pub(crate) const SUPPORTED_MODIFIERS: &[SemanticTokenModifier] = &[
             SemanticTokenModifier::DOCUMENTATION,
             SemanticTokenModifier::DECLARATION,
@@ -70,6 +73,26 @@ define_semantic_token_modifiers![
     (MUTABLE, "mutable"),
     (UNSAFE, "unsafe"),
     (ATTRIBUTE_MODIFIER, "attribute"),
+    (REDUNDANT, "redundant"),
+    (ASYNC, "async"),
+    (PROVIDED, "provided"),
+    (ERROR, "error"),
+    (ASSOCIATED, "associated"),
+    (SHORTHAND, "shorthand"),
+    (NON_EXHAUSTIVE, "nonExhaustive"),
+    (SMART_POINTER, "smartPointer"),
+    (FALLIBLE, "fallible"),
+    (CONSTRUCTOR, "constructor"),
+    (CONSTRAINT, "constraint"),
+    (CRATE_ROOT, "crateRoot"),
+    (HEADING, "heading"),
+    (CODE, "code"),
+    (EMPHASIS, "emphasis"),
+    (MARKER, "marker"),
+    (DROP, "drop"),
+    (CHAIN_START, "chainStart"),
+    (CHAIN_MEMBER, "chainMember"),
+    (BUILTIN, "builtin"),
 ];
 
 #[derive(Default)]
@@ -317,6 +317,8 @@ fn semantic_token_type_and_modifiers(
         HighlightTag::Keyword => lsp_types::SemanticTokenType::KEYWORD,
         HighlightTag::UnresolvedReference => semantic_tokens::UNRESOLVED_REFERENCE,
         HighlightTag::FormatSpecifier => semantic_tokens::FORMAT_SPECIFIER,
+        HighlightTag::Punctuation => semantic_tokens::PUNCTUATION,
+        HighlightTag::Operator => lsp_types::SemanticTokenType::OPERATOR,
     };
 
     for modifier in highlight.modifiers.iter() {
@@ -326,6 +328,27 @@ fn semantic_token_type_and_modifiers(
             HighlightModifier::ControlFlow => semantic_tokens::CONTROL_FLOW,
             HighlightModifier::Mutable => semantic_tokens::MUTABLE,
             HighlightModifier::Unsafe => semantic_tokens::UNSAFE,
+            HighlightModifier::Redundant => semantic_tokens::REDUNDANT,
+            HighlightModifier::Async => semantic_tokens::ASYNC,
+            HighlightModifier::Provided => semantic_tokens::PROVIDED,
+            HighlightModifier::Error => semantic_tokens::ERROR,
+            HighlightModifier::Associated => semantic_tokens::ASSOCIATED,
+            HighlightModifier::Shorthand => semantic_tokens::SHORTHAND,
+            HighlightModifier::Const => semantic_tokens::CONSTANT,
+            HighlightModifier::NonExhaustive => semantic_tokens::NON_EXHAUSTIVE,
+            HighlightModifier::SmartPointer => semantic_tokens::SMART_POINTER,
+            HighlightModifier::Fallible => semantic_tokens::FALLIBLE,
+            HighlightModifier::Constructor => semantic_tokens::CONSTRUCTOR,
+            HighlightModifier::Constraint => semantic_tokens::CONSTRAINT,
+            HighlightModifier::CrateRoot => semantic_tokens::CRATE_ROOT,
+            HighlightModifier::Heading => semantic_tokens::HEADING,
+            HighlightModifier::Code => semantic_tokens::CODE,
+            HighlightModifier::Emphasis => semantic_tokens::EMPHASIS,
+            HighlightModifier::Marker => semantic_tokens::MARKER,
+            HighlightModifier::Drop => semantic_tokens::DROP,
+            HighlightModifier::ChainStart => semantic_tokens::CHAIN_START,
+            HighlightModifier::ChainMember => semantic_tokens::CHAIN_MEMBER,
+            HighlightModifier::Builtin => semantic_tokens::BUILTIN,
         };
         mods |= modifier;
     }
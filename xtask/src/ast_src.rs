@@ -164,6 +164,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "RECORD_FIELD",
         "EFFECT_EXPR",
         "BOX_EXPR",
+        "LET_EXPR",
         // postfix
         "CALL_EXPR",
         "INDEX_EXPR",
@@ -1178,6 +1179,15 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
         /// [RFC](https://github.com/rust-lang/rfcs/blob/0806be4f282144cfcd55b1d20284b43f87cbe1c6/text/0809-box-and-in-for-stdlib.md)
         struct BoxExpr: AttrsOwner { T![box], Expr }
 
+        /// `let` binding used as an operand of a condition chain.
+        ///
+        /// ```
+        /// if ❰ let Some(a) = x ❱ && ❰ let Some(b) = y ❱ {}
+        /// ```
+        ///
+        /// [RFC](https://github.com/rust-lang/rfcs/blob/master/text/2497-if-let-chains.md)
+        struct LetExpr { T![let], Pat, T![=], Expr }
+
         /// Range operator call.
         ///
         /// ```
@@ -2192,6 +2202,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             Literal,
             MacroCall,
             BoxExpr,
+            LetExpr,
         }
 
         /// Any kind of pattern